@@ -0,0 +1,235 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use crate::error::{self, Error, Result};
+use crate::runner::DayResult;
+use crate::solver::Answer;
+
+/// default file with the confirmed answers
+pub const DEFAULT_ANSWERS: &str = "answers.toml";
+
+/// Confirmed answers per day and part.
+///
+/// The file is a small subset of TOML, one table per day with the answers
+/// as integers or strings:
+///
+/// ```toml
+/// [day10]
+/// part1 = 13220
+/// part2 = "RUAKHBEK"
+/// ```
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Answers {
+    days: BTreeMap<u32, [Option<Answer>; 2]>,
+}
+
+/// outcome of comparing a computed answer with the confirmed one, `Error`
+/// when the input or the solver of the day failed and there is no answer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Pass,
+    Fail { expected: Answer },
+    Missing,
+    Error,
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Pass => write!(f, "PASS"),
+            Verdict::Fail { expected } => write!(f, "FAIL (expected {})", expected),
+            Verdict::Missing => write!(f, "MISSING"),
+            Verdict::Error => write!(f, "FAIL (no answer)"),
+        }
+    }
+}
+
+impl Verdict {
+    pub fn is_failure(&self) -> bool {
+        matches!(self, Verdict::Fail { .. } | Verdict::Error)
+    }
+}
+
+impl Answers {
+    pub fn read(path: &Path) -> Result<Answers> {
+        let content = fs::read_to_string(path).map_err(|source| Error::MissingInput {
+            path: Some(path.to_path_buf()),
+            source,
+        })?;
+        Answers::parse(&content)
+    }
+
+    pub fn parse(content: &str) -> Result<Answers> {
+        let mut answers = Answers::default();
+        let mut day: Option<u32> = None;
+
+        for (index, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if let Some(table) = line.strip_prefix('[') {
+                let table = table
+                    .strip_suffix(']')
+                    .ok_or_else(|| Error::line(index, format!("unclosed table {:?}", line)))?;
+                let number = table.strip_prefix("day").ok_or_else(|| {
+                    Error::parse(index + 1, 2, format!("expected a day, found {:?}", table))
+                })?;
+                let number = error::number::<u32>(number, index, 4)?;
+                if answers.days.contains_key(&number) {
+                    return Err(Error::line(
+                        index,
+                        format!("day {} is listed twice", number),
+                    ));
+                }
+                answers.days.insert(number, [None, None]);
+                day = Some(number);
+                continue;
+            }
+
+            let (key, value) = line.split_once('=').ok_or_else(|| {
+                Error::line(index, format!("expected `key = value`, found {:?}", line))
+            })?;
+            let day = day.ok_or_else(|| Error::line(index, "answer outside of a [dayNN] table"))?;
+            let part = match key.trim() {
+                "part1" => 0,
+                "part2" => 1,
+                key => return Err(Error::line(index, format!("unknown key {:?}", key))),
+            };
+            let offset = line.len() - value.trim_start().len();
+            let answer = parse_value(value.trim(), index, offset)?;
+            answers.days.get_mut(&day).unwrap()[part] = Some(answer);
+        }
+
+        Ok(answers)
+    }
+
+    pub fn get(&self, day: u32, part: usize) -> Option<&Answer> {
+        self.days.get(&day)?.get(part - 1)?.as_ref()
+    }
+
+    /// compares both parts of `result` with the confirmed answers
+    pub fn check(&self, result: &DayResult) -> [Verdict; 2] {
+        [(1, &result.part1), (2, &result.part2)].map(|(part, answer)| {
+            match self.get(result.day, part) {
+                None => Verdict::Missing,
+                Some(expected) if expected == answer => Verdict::Pass,
+                Some(expected) => Verdict::Fail {
+                    expected: expected.clone(),
+                },
+            }
+        })
+    }
+}
+
+/// parses an integer or a basic string, `offset` is the 0-based column of the value
+fn parse_value(value: &str, index: usize, offset: usize) -> Result<Answer> {
+    let Some(quoted) = value.strip_prefix('"') else {
+        let number = value.split('#').next().unwrap().trim_end();
+        return Ok(Answer::Number(error::number::<i64>(number, index, offset)?));
+    };
+
+    let mut text = String::new();
+    let mut chars = quoted.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                let rest = chars.as_str().trim();
+                if !rest.is_empty() && !rest.starts_with('#') {
+                    return Err(Error::parse(
+                        index + 1,
+                        offset + value.len() - chars.as_str().len() + 1,
+                        format!("unexpected {:?} after the string", rest),
+                    ));
+                }
+                return Ok(Answer::Text(text));
+            }
+            '\\' => match chars.next() {
+                Some('"') => text.push('"'),
+                Some('\\') => text.push('\\'),
+                other => {
+                    return Err(Error::line(
+                        index,
+                        format!("unsupported escape \\{}", other.unwrap_or(' ')),
+                    ))
+                }
+            },
+            c => text.push(c),
+        }
+    }
+    Err(Error::line(index, "unterminated string"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    const ANSWERS: &str = r#"
+# a comment
+[day01]
+part1 = 24000
+part2 = 45000 # another comment
+
+[day05]
+part1 = "CMZ"   # with a comment
+"#;
+
+    #[test]
+    fn parse() {
+        let answers = Answers::parse(ANSWERS).unwrap();
+        assert_eq!(answers.get(1, 1), Some(&Answer::from(24000)));
+        assert_eq!(answers.get(1, 2), Some(&Answer::from(45000)));
+        assert_eq!(answers.get(5, 1), Some(&Answer::from("CMZ")));
+        assert_eq!(answers.get(5, 2), None);
+        assert_eq!(answers.get(2, 1), None);
+    }
+
+    #[test]
+    fn parse_errors() {
+        for (content, line) in [
+            ("part1 = 1", 1),
+            ("[day01]\npart3 = 1", 2),
+            ("[day01]\npart1 = one", 2),
+            ("[day01]\npart1 = \"open", 2),
+            ("[day01]\n[day01]", 2),
+            ("[days]", 1),
+        ] {
+            match Answers::parse(content) {
+                Err(Error::Parse { line: l, .. }) => assert_eq!(l, line, "{:?}", content),
+                other => panic!("expected a parse error for {:?}, got {:?}", content, other),
+            }
+        }
+    }
+
+    #[test]
+    fn check() {
+        let answers = Answers::parse(ANSWERS).unwrap();
+        let result = |day, part1: Answer, part2: Answer| DayResult {
+            day,
+            parse_time: Duration::ZERO,
+            part1,
+            part1_time: Duration::ZERO,
+            part2,
+            part2_time: Duration::ZERO,
+        };
+
+        assert_eq!(
+            answers.check(&result(1, 24000.into(), 45001.into())),
+            [
+                Verdict::Pass,
+                Verdict::Fail {
+                    expected: 45000.into()
+                }
+            ]
+        );
+        assert_eq!(
+            answers.check(&result(5, "CMZ".into(), "MCD".into())),
+            [Verdict::Pass, Verdict::Missing]
+        );
+        assert!(Verdict::Error.is_failure() && !Verdict::Missing.is_failure());
+    }
+}
@@ -1,38 +1,187 @@
-use parse_int::parse;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-
-pub fn aoc01() {
-    let reader = BufReader::new(File::open("input-01").unwrap());
-
-    let mut elves: Vec<i32> = Vec::new();
-    let mut current_calories = 0;
-
-    for (_index, line) in reader.lines().enumerate() {
-        let line = line.unwrap();
-
-        if line.len() < 1 {
-            elves.push(current_calories);
-            current_calories = 0;
-            continue;
-        } else {
-            if let Ok(i) = parse::<i32>(&line) {
-                current_calories += i;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use crate::error::{self, Result};
+use crate::input;
+use crate::solver::{Answer, Solver};
+
+/// The food items every elve carries, in the order of the input.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Inventory {
+    elves: Vec<Vec<usize>>,
+}
+
+impl Inventory {
+    /// number of elves
+    pub fn len(&self) -> usize {
+        self.elves.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.elves.is_empty()
+    }
+
+    /// calories of each item of the elve with the `index`
+    pub fn items(&self, index: usize) -> &[usize] {
+        &self.elves[index]
+    }
+
+    /// number of items of every elve
+    pub fn item_counts(&self) -> Vec<usize> {
+        self.elves.iter().map(Vec::len).collect()
+    }
+
+    /// calories carried by the elve with the `index`
+    pub fn calories(&self, index: usize) -> usize {
+        self.elves[index].iter().sum()
+    }
+
+    /// calories carried by every elve
+    pub fn totals(&self) -> Vec<usize> {
+        (0..self.len()).map(|i| self.calories(i)).collect()
+    }
+
+    /// calories carried by all elves together
+    pub fn total(&self) -> usize {
+        self.elves.iter().flatten().sum()
+    }
+
+    pub fn mean(&self) -> f64 {
+        self.total() as f64 / self.len().max(1) as f64
+    }
+
+    pub fn median(&self) -> f64 {
+        let mut totals = self.totals();
+        if totals.is_empty() {
+            return 0.0;
+        }
+        let middle = totals.len() / 2;
+        let (below, upper, _) = totals.select_nth_unstable(middle);
+        let upper = *upper as f64;
+        match below.iter().max() {
+            Some(&lower) if self.len().is_multiple_of(2) => (lower as f64 + upper) / 2.0,
+            _ => upper,
+        }
+    }
+
+    /// The `k` elves carrying the most calories as `(index, calories)`, most first.
+    ///
+    /// Only the best `k` are kept in a heap while going through the elves, ties
+    /// go to the elve coming first.
+    pub fn top(&self, k: usize) -> Vec<(usize, usize)> {
+        let mut heap: BinaryHeap<Reverse<(usize, Reverse<usize>)>> = BinaryHeap::new();
+        for (index, calories) in self.totals().into_iter().enumerate() {
+            heap.push(Reverse((calories, Reverse(index))));
+            if heap.len() > k {
+                heap.pop();
             }
         }
+        heap.into_sorted_vec()
+            .into_iter()
+            .map(|Reverse((calories, Reverse(index)))| (index, calories))
+            .collect()
     }
 
-    elves.sort();
+    /// the fewest elves that together carry at least `calories`, `None` if all of them do not
+    pub fn carrying(&self, calories: usize) -> Option<Vec<usize>> {
+        let mut heap: BinaryHeap<(usize, Reverse<usize>)> = self
+            .totals()
+            .into_iter()
+            .enumerate()
+            .map(|(index, c)| (c, Reverse(index)))
+            .collect();
+        let mut elves = vec![];
+        let mut carried = 0;
+        while carried < calories {
+            let (c, Reverse(index)) = heap.pop()?;
+            carried += c;
+            elves.push(index);
+        }
+        Some(elves)
+    }
+}
+
+pub struct Aoc01;
+
+impl Solver for Aoc01 {
+    type Input = Inventory;
+
+    fn parse(&self, input: &str) -> Result<Inventory> {
+        let mut elves = vec![];
+        for record in input::split_records(input) {
+            let mut items = vec![];
+            for (index, line) in record.lines() {
+                items.push(error::number::<usize>(line, index, 0)?);
+            }
+            elves.push(items);
+        }
+        Ok(Inventory { elves })
+    }
+
+    fn part1(&self, inventory: &Inventory) -> Result<Answer> {
+        let top = inventory.top(1);
+        Ok(top.iter().map(|(_, c)| c).sum::<usize>().into())
+    }
+
+    fn part2(&self, inventory: &Inventory) -> Result<Answer> {
+        let top = inventory.top(3);
+        Ok(top.iter().map(|(_, c)| c).sum::<usize>().into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+1000
+2000
+3000
+
+4000
+
+5000
+6000
 
-    println!("fattest: {}", elves[elves.len() - 1]);
+7000
+8000
+9000
 
-    let mut sum = 0;
-    for elve in elves.iter().rev().take(3) {
-        sum += elve;
+10000
+
+";
+
+    #[test]
+    fn example() {
+        let inventory = Aoc01.parse(EXAMPLE).unwrap();
+        assert_eq!(Aoc01.part1(&inventory).unwrap(), Answer::from(24000));
+        assert_eq!(Aoc01.part2(&inventory).unwrap(), Answer::from(45000));
+    }
+
+    #[test]
+    fn no_trailing_blank_line() {
+        let inventory = Aoc01.parse(EXAMPLE.trim_end()).unwrap();
+        assert_eq!(inventory.len(), 5);
+        assert_eq!(Aoc01.part2(&inventory).unwrap(), Answer::from(45000));
     }
-    println!("sum of fattest 3: {}", sum);
 
-    let x: i32 = elves.iter().rev().take(3).sum();
-    println!("{}", x);
-    println!("{}", elves.iter().rev().take(3).sum::<i32>());
+    #[test]
+    fn statistics() {
+        let inventory = Aoc01.parse(EXAMPLE).unwrap();
+        assert_eq!(inventory.top(2), [(3, 24000), (2, 11000)]);
+        assert_eq!(inventory.top(9).len(), 5);
+        assert_eq!(inventory.total(), 55000);
+        assert_eq!(inventory.mean(), 11000.0);
+        assert_eq!(inventory.median(), 10000.0);
+        assert_eq!(inventory.item_counts(), [3, 1, 2, 3, 1]);
+        assert_eq!(inventory.items(2), [5000, 6000]);
+        assert_eq!(inventory.carrying(30000), Some(vec![3, 2]));
+        assert_eq!(inventory.carrying(0), Some(vec![]));
+        assert_eq!(inventory.carrying(55001), None);
+
+        let even = Aoc01.parse("1\n\n4\n\n2\n\n8").unwrap();
+        assert_eq!(even.median(), 3.0);
+        // ties go to the first elve
+        assert_eq!(Aoc01.parse("5\n\n5\n\n5").unwrap().top(2), [(0, 5), (1, 5)]);
+    }
 }
@@ -1,63 +1,538 @@
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-
-fn my_score(line: &str) -> i32 {
-    if line.contains("X") {
-        return 1;
-    }
-    if line.contains("Y") {
-        return 2;
-    }
-    if line.contains("Z") {
-        return 3;
-    }
-    panic!("ne message");
-}
-
-fn outcome(line: &str) -> i32 {
-    match line {
-        "A X" => return 3,
-        "A Y" => return 6,
-        "A Z" => return 0,
-        "B X" => return 0,
-        "B Y" => return 3,
-        "B Z" => return 6,
-        "C X" => return 6,
-        "C Y" => return 0,
-        "C Z" => return 3,
-        // _ => return 0
-        &_ => (),
-    };
-    panic!("ne message");
-}
-fn rewrite_line(line: &str) -> &'static str {
-    match line {
-        "A X" => return "A Z",
-        "A Y" => return "A X",
-        "A Z" => return "A Y",
-        "B X" => return "B X",
-        "B Y" => return "B Y",
-        "B Z" => return "B Z",
-        "C X" => return "C Y",
-        "C Y" => return "C Z",
-        "C Z" => return "C X",
-        _ => panic!("ne message"),
-    }
-}
-
-pub fn aoc02() {
-    let reader = BufReader::new(File::open("input-02").unwrap());
-    let mut score1: i32 = 0;
-    let mut score2: i32 = 0;
-
-    for (_index, line) in reader.lines().enumerate() {
-        let line = line.unwrap();
-
-        score1 += my_score(&line) + outcome(&line);
-        let line = rewrite_line(&line);
-        score2 += my_score(&line) + outcome(&line);
-    }
-
-    println!("score part 1: {}", score1);
-    println!("score part 2: {}", score2);
+use rand::distributions::{Distribution as _, WeightedIndex};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::cmp::{Ordering, Reverse};
+
+use crate::error::{Error, Result};
+use crate::solver::{Answer, Solver};
+
+/// a move, the index into the moves of the [`Rules`]
+pub type Move = usize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Loss,
+    Draw,
+    Win,
+}
+
+impl Outcome {
+    pub const ALL: [Outcome; 3] = [Outcome::Loss, Outcome::Draw, Outcome::Win];
+
+    pub fn score(&self) -> usize {
+        match self {
+            Outcome::Loss => 0,
+            Outcome::Draw => 3,
+            Outcome::Win => 6,
+        }
+    }
+}
+
+/// The moves of a game and which move beats which.
+///
+/// Playing a move scores its position in the list, starting at 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rules {
+    moves: Vec<String>,
+    /// `beats[a][b]` is true if `a` wins against `b`
+    beats: Vec<Vec<bool>>,
+}
+
+impl Rules {
+    /// a game with the `moves` where `a` beats `b` for every `(a, b)` in `beats`
+    pub fn new(moves: &[&str], beats: &[(Move, Move)]) -> Result<Rules> {
+        let n = moves.len();
+        if n == 0 {
+            return Err(Error::invalid("a game needs at least one move"));
+        }
+        let mut table = vec![vec![false; n]; n];
+        for &(a, b) in beats {
+            if a >= n || b >= n || a == b || table[b][a] {
+                return Err(Error::invalid(format!(
+                    "{} cannot beat {}",
+                    moves.get(a).unwrap_or(&"?"),
+                    moves.get(b).unwrap_or(&"?")
+                )));
+            }
+            table[a][b] = true;
+        }
+        Ok(Rules {
+            moves: moves.iter().map(|m| m.to_string()).collect(),
+            beats: table,
+        })
+    }
+
+    /// Every move beats the ones an odd number of steps before it in the circle.
+    ///
+    /// With an odd number of moves this is fair, every move beats exactly
+    /// half of the others.
+    pub fn cyclic(moves: &[&str]) -> Result<Rules> {
+        let n = moves.len();
+        if n.is_multiple_of(2) {
+            return Err(Error::invalid(format!(
+                "a cyclic game needs an odd number of moves, not {}",
+                n
+            )));
+        }
+        let beats: Vec<(Move, Move)> = (0..n)
+            .flat_map(|a| (1..n).step_by(2).map(move |k| (a, (a + n - k) % n)))
+            .collect();
+        Rules::new(moves, &beats)
+    }
+
+    pub fn rock_paper_scissors() -> Rules {
+        Rules::cyclic(&["rock", "paper", "scissors"]).unwrap()
+    }
+
+    pub fn rock_paper_scissors_lizard_spock() -> Rules {
+        Rules::cyclic(&["rock", "paper", "scissors", "Spock", "lizard"]).unwrap()
+    }
+
+    pub fn moves(&self) -> &[String] {
+        &self.moves
+    }
+
+    /// `m` if it is one of the moves of the game
+    pub fn check(&self, m: Move) -> Result<Move> {
+        if m < self.moves.len() {
+            Ok(m)
+        } else {
+            Err(Error::invalid(format!(
+                "there is no move {} in a game of {} moves",
+                m,
+                self.moves.len()
+            )))
+        }
+    }
+
+    pub fn outcome(&self, mine: Move, theirs: Move) -> Outcome {
+        if self.beats[mine][theirs] {
+            Outcome::Win
+        } else if self.beats[theirs][mine] {
+            Outcome::Loss
+        } else {
+            Outcome::Draw
+        }
+    }
+
+    /// my score for a single round
+    pub fn score(&self, mine: Move, theirs: Move) -> usize {
+        mine + 1 + self.outcome(mine, theirs).score()
+    }
+
+    /// the move that scores most against `theirs`
+    pub fn best_response(&self, theirs: Move) -> Move {
+        (0..self.moves.len())
+            .max_by_key(|&mine| self.score(mine, theirs))
+            .unwrap()
+    }
+
+    /// the first move that ends in the `outcome` against `theirs`
+    pub fn answer(&self, theirs: Move, outcome: Outcome) -> Option<Move> {
+        (0..self.moves.len()).find(|&mine| self.outcome(mine, theirs) == outcome)
+    }
+}
+
+/// one line of the strategy guide, the opponent's move and the column telling me what to do
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Round {
+    pub theirs: Move,
+    pub column: usize,
+}
+
+/// Reads a strategy guide, `opponent` and `own` are the letters of each column.
+pub fn parse_guide(input: &str, opponent: &str, own: &str) -> Result<Vec<Round>> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(index, line)| {
+            let mut chars = line.chars();
+            match (chars.next(), chars.next(), chars.next(), chars.next()) {
+                (Some(a), Some(' '), Some(b), None) => match (opponent.find(a), own.find(b)) {
+                    (Some(theirs), Some(column)) => Ok(Round { theirs, column }),
+                    _ => Err(Error::line(index, format!("invalid round {:?}", line))),
+                },
+                _ => Err(Error::line(index, format!("invalid round {:?}", line))),
+            }
+        })
+        .collect()
+}
+
+/// how the second column of the strategy guide is meant
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Decoder {
+    /// the move I should play
+    Move,
+    /// whether I should lose, draw or win
+    Outcome,
+}
+
+impl Decoder {
+    /// the move I play in the `round`
+    pub fn decode(&self, rules: &Rules, round: &Round) -> Result<Move> {
+        rules.check(round.theirs)?;
+        let mine = match self {
+            Decoder::Move => Some(round.column).filter(|&m| m < rules.moves.len()),
+            Decoder::Outcome => Outcome::ALL
+                .get(round.column)
+                .and_then(|&outcome| rules.answer(round.theirs, outcome)),
+        };
+        mine.ok_or_else(|| {
+            Error::invalid(format!(
+                "cannot decode column {} of {:?}",
+                round.column, round
+            ))
+        })
+    }
+
+    /// the moves I play when I follow the guide
+    pub fn moves(&self, rules: &Rules, rounds: &[Round]) -> Result<Vec<Move>> {
+        rounds.iter().map(|r| self.decode(rules, r)).collect()
+    }
+
+    /// my total score when I follow the guide
+    pub fn total_score(&self, rules: &Rules, rounds: &[Round]) -> Result<usize> {
+        let mut score = 0;
+        for round in rounds.iter() {
+            score += rules.score(self.decode(rules, round)?, round.theirs);
+        }
+        Ok(score)
+    }
+}
+
+/// my best answers to the opponent's moves and the score they reach
+pub fn optimal_response(rules: &Rules, theirs: &[Move]) -> Result<(Vec<Move>, usize)> {
+    let mine = theirs
+        .iter()
+        .map(|&t| Ok(rules.best_response(rules.check(t)?)))
+        .collect::<Result<Vec<Move>>>()?;
+    let score = mine
+        .iter()
+        .zip(theirs.iter())
+        .map(|(&m, &t)| rules.score(m, t))
+        .sum();
+    Ok((mine, score))
+}
+
+/// seed for all simulations, so the results can be reproduced
+pub const SEED: u64 = 2022;
+
+/// An opponent that picks its moves at random, each move with its own weight.
+#[derive(Debug, Clone)]
+pub struct Opponent {
+    weights: WeightedIndex<f64>,
+}
+
+impl Opponent {
+    pub fn uniform(rules: &Rules) -> Opponent {
+        Opponent::biased(rules, &vec![1.0; rules.moves.len()]).unwrap()
+    }
+
+    /// `weights` holds one weight per move of the game
+    pub fn biased(rules: &Rules, weights: &[f64]) -> Result<Opponent> {
+        if weights.len() != rules.moves.len() {
+            return Err(Error::invalid(format!(
+                "expected {} weights, one per move, not {}",
+                rules.moves.len(),
+                weights.len()
+            )));
+        }
+        let weights = WeightedIndex::new(weights)
+            .map_err(|e| Error::invalid(format!("invalid weights {:?}: {}", weights, e)))?;
+        Ok(Opponent { weights })
+    }
+
+    pub fn play(&self, rng: &mut impl Rng) -> Move {
+        self.weights.sample(rng)
+    }
+}
+
+/// total scores of many games, sorted
+#[derive(Debug, Clone, PartialEq)]
+pub struct Distribution {
+    pub scores: Vec<usize>,
+}
+
+impl Distribution {
+    pub fn min(&self) -> usize {
+        self.scores.first().copied().unwrap_or(0)
+    }
+
+    pub fn max(&self) -> usize {
+        self.scores.last().copied().unwrap_or(0)
+    }
+
+    pub fn mean(&self) -> f64 {
+        self.scores.iter().sum::<usize>() as f64 / self.scores.len().max(1) as f64
+    }
+
+    /// the score `q` of all games stayed below, like 0.5 for the median
+    pub fn quantile(&self, q: f64) -> usize {
+        if self.scores.is_empty() {
+            return 0;
+        }
+        self.scores[((self.scores.len() - 1) as f64 * q).round() as usize]
+    }
+}
+
+/// Plays the guide `games` times against a random `opponent` instead of the moves it lists.
+pub fn score_distribution(
+    rules: &Rules,
+    decoder: Decoder,
+    rounds: &[Round],
+    opponent: &Opponent,
+    games: usize,
+    seed: u64,
+) -> Result<Distribution> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut scores = vec![];
+    for _ in 0..games {
+        let mut score = 0;
+        for round in rounds.iter() {
+            let round = Round {
+                theirs: opponent.play(&mut rng),
+                column: round.column,
+            };
+            score += rules.score(decoder.decode(rules, &round)?, round.theirs);
+        }
+        scores.push(score);
+    }
+    scores.sort();
+    Ok(Distribution { scores })
+}
+
+/// results of one guide in a [`tournament`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Standing {
+    pub name: String,
+    pub wins: usize,
+    pub draws: usize,
+    pub losses: usize,
+    /// points from all rounds of all matches
+    pub score: usize,
+}
+
+/// Lets every guide play against every other one, best guide first.
+///
+/// A match lasts as many rounds as the shorter guide has, the guide with
+/// more points in the match wins it.
+pub fn tournament(rules: &Rules, guides: &[(&str, Vec<Move>)]) -> Result<Vec<Standing>> {
+    for (_, moves) in guides.iter() {
+        for &m in moves.iter() {
+            rules.check(m)?;
+        }
+    }
+
+    let mut standings: Vec<Standing> = guides
+        .iter()
+        .map(|(name, _)| Standing {
+            name: name.to_string(),
+            wins: 0,
+            draws: 0,
+            losses: 0,
+            score: 0,
+        })
+        .collect();
+
+    for a in 0..guides.len() {
+        for b in a + 1..guides.len() {
+            let (mut score_a, mut score_b) = (0, 0);
+            for (&move_a, &move_b) in guides[a].1.iter().zip(guides[b].1.iter()) {
+                score_a += rules.score(move_a, move_b);
+                score_b += rules.score(move_b, move_a);
+            }
+            standings[a].score += score_a;
+            standings[b].score += score_b;
+            match score_a.cmp(&score_b) {
+                Ordering::Greater => {
+                    standings[a].wins += 1;
+                    standings[b].losses += 1;
+                }
+                Ordering::Less => {
+                    standings[a].losses += 1;
+                    standings[b].wins += 1;
+                }
+                Ordering::Equal => {
+                    standings[a].draws += 1;
+                    standings[b].draws += 1;
+                }
+            }
+        }
+    }
+
+    standings.sort_by_key(|s| Reverse((s.wins, s.draws, s.score)));
+    Ok(standings)
+}
+
+pub struct Aoc02;
+
+impl Solver for Aoc02 {
+    type Input = Vec<Round>;
+
+    fn parse(&self, input: &str) -> Result<Vec<Round>> {
+        parse_guide(input, "ABC", "XYZ")
+    }
+
+    fn part1(&self, rounds: &Vec<Round>) -> Result<Answer> {
+        let rules = Rules::rock_paper_scissors();
+        Ok(Decoder::Move.total_score(&rules, rounds)?.into())
+    }
+
+    fn part2(&self, rounds: &Vec<Round>) -> Result<Answer> {
+        let rules = Rules::rock_paper_scissors();
+        Ok(Decoder::Outcome.total_score(&rules, rounds)?.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+A Y
+B X
+C Z
+";
+
+    #[test]
+    fn example() {
+        let rounds = Aoc02.parse(EXAMPLE).unwrap();
+        assert_eq!(Aoc02.part1(&rounds).unwrap(), Answer::from(15));
+        assert_eq!(Aoc02.part2(&rounds).unwrap(), Answer::from(12));
+    }
+
+    #[test]
+    fn rules() {
+        let rps = Rules::rock_paper_scissors();
+        assert_eq!(rps.outcome(1, 0), Outcome::Win);
+        assert_eq!(rps.outcome(0, 1), Outcome::Loss);
+        assert_eq!(rps.outcome(2, 2), Outcome::Draw);
+        assert_eq!(rps.answer(0, Outcome::Loss), Some(2));
+
+        let rpsls = Rules::rock_paper_scissors_lizard_spock();
+        let names: Vec<&str> = rpsls.moves().iter().map(String::as_str).collect();
+        let spock = names.iter().position(|m| *m == "Spock").unwrap();
+        let lizard = names.iter().position(|m| *m == "lizard").unwrap();
+        assert_eq!(rpsls.outcome(spock, 2), Outcome::Win);
+        assert_eq!(rpsls.outcome(spock, 0), Outcome::Win);
+        assert_eq!(rpsls.outcome(lizard, spock), Outcome::Win);
+        assert_eq!(rpsls.outcome(lizard, 1), Outcome::Win);
+        assert_eq!(rpsls.outcome(0, lizard), Outcome::Win);
+
+        let seven = Rules::cyclic(&["a", "b", "c", "d", "e", "f", "g"]).unwrap();
+        for mine in 0..7 {
+            let wins = (0..7)
+                .filter(|&theirs| seven.outcome(mine, theirs) == Outcome::Win)
+                .count();
+            assert_eq!(wins, 3);
+        }
+        assert!(matches!(Rules::cyclic(&["a", "b"]), Err(Error::Invalid(_))));
+        assert!(matches!(
+            Rules::new(&["a", "b"], &[(0, 1), (1, 0)]),
+            Err(Error::Invalid(_))
+        ));
+        assert!(matches!(Rules::new(&[], &[]), Err(Error::Invalid(_))));
+    }
+
+    #[test]
+    fn larger_guides() {
+        let rules = Rules::rock_paper_scissors_lizard_spock();
+        let rounds = parse_guide("A V\nE Z\n", "ABCDE", "VWXYZ").unwrap();
+        // rock against rock and lizard against lizard, both draws
+        assert_eq!(
+            Decoder::Move.total_score(&rules, &rounds).unwrap(),
+            1 + 3 + 5 + 3
+        );
+        assert!(matches!(
+            Decoder::Outcome.total_score(&rules, &rounds),
+            Err(Error::Invalid(_))
+        ));
+        assert!(Aoc02.parse("A W\n").is_err());
+
+        // the guide knows more moves than rock paper scissors
+        let rps = Rules::rock_paper_scissors();
+        let rounds = parse_guide("E V\n", "ABCDE", "VWXYZ").unwrap();
+        assert!(matches!(
+            Decoder::Move.total_score(&rps, &rounds),
+            Err(Error::Invalid(_))
+        ));
+        let rounds = parse_guide("A Z\n", "ABCDE", "VWXYZ").unwrap();
+        assert!(matches!(
+            Decoder::Move.total_score(&rps, &rounds),
+            Err(Error::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn optimizer() {
+        let rules = Rules::rock_paper_scissors();
+        let (mine, score) = optimal_response(&rules, &[0, 1, 2]).unwrap();
+        assert_eq!(mine, [1, 2, 0]);
+        assert_eq!(score, 8 + 9 + 7);
+        assert!(matches!(
+            optimal_response(&rules, &[0, 3]),
+            Err(Error::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn distribution() {
+        let rules = Rules::rock_paper_scissors();
+        let rounds = Aoc02.parse(EXAMPLE).unwrap();
+
+        // an opponent that always plays rock cannot surprise anyone
+        let rock = Opponent::biased(&rules, &[1.0, 0.0, 0.0]).unwrap();
+        let fixed = score_distribution(&rules, Decoder::Move, &rounds, &rock, 10, SEED).unwrap();
+        assert_eq!((fixed.min(), fixed.max()), (8 + 4 + 3, 8 + 4 + 3));
+
+        let uniform = Opponent::uniform(&rules);
+        let random =
+            score_distribution(&rules, Decoder::Move, &rounds, &uniform, 1000, SEED).unwrap();
+        assert_eq!(
+            random,
+            score_distribution(&rules, Decoder::Move, &rounds, &uniform, 1000, SEED).unwrap()
+        );
+        assert!(random.min() >= 6 && random.max() <= 24);
+        // every round scores 3 points on average plus the shapes 2, 1 and 3
+        assert!((random.mean() - 15.0).abs() < 0.5);
+        assert!(random.quantile(0.0) == random.min() && random.quantile(1.0) == random.max());
+
+        // following the outcomes always draws, loses and wins once, only the shapes vary
+        let outcomes =
+            score_distribution(&rules, Decoder::Outcome, &rounds, &uniform, 100, SEED).unwrap();
+        assert!(outcomes.min() >= 9 + 3 && outcomes.max() <= 9 + 9);
+        assert!(matches!(
+            Opponent::biased(&rules, &[0.0, 0.0, 0.0]),
+            Err(Error::Invalid(_))
+        ));
+        assert!(matches!(
+            Opponent::biased(&rules, &[0.0, 0.0, 0.0, 0.0, 1.0]),
+            Err(Error::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn tournaments() {
+        let rules = Rules::rock_paper_scissors();
+        let standings = tournament(
+            &rules,
+            &[
+                ("rocks", vec![0, 0, 0]),
+                ("papers", vec![1, 1, 1]),
+                ("mixed", vec![0, 1, 2]),
+            ],
+        )
+        .unwrap();
+        let names: Vec<&str> = standings.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["papers", "mixed", "rocks"]);
+        assert_eq!(
+            (standings[0].wins, standings[0].draws, standings[0].losses),
+            (1, 1, 0)
+        );
+        assert_eq!(standings[2].losses, 2);
+        assert!(matches!(
+            tournament(&rules, &[("a", vec![7]), ("b", vec![0])]),
+            Err(Error::Invalid(_))
+        ));
+    }
 }
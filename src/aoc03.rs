@@ -1,60 +1,221 @@
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-
-const LETTERS: &str = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
-
-fn aoc03_1() {
-    println!("solving AOC day 3 part 1");
-    let reader = BufReader::new(File::open("input-03").unwrap());
-    let mut score: usize = 0;
-
-    for (_index, line) in reader.lines().enumerate() {
-        let line = line.unwrap();
-        for c in line.chars() {
-            if line.rfind(c).unwrap() >= line.len() / 2 {
-                score += LETTERS.find(c).unwrap() + 1;
-                break;
-            }
+use std::fmt;
+
+use crate::error::{Error, Result};
+use crate::solver::{Answer, Solver};
+
+/// number of rucksacks per group of elves
+pub const GROUP_SIZE: usize = 3;
+
+/// priority of an item, 1 to 26 for `a` to `z` and 27 to 52 for `A` to `Z`
+pub fn priority(item: char) -> Option<usize> {
+    match item {
+        'a'..='z' => Some(item as usize - 'a' as usize + 1),
+        'A'..='Z' => Some(item as usize - 'A' as usize + 27),
+        _ => None,
+    }
+}
+
+fn item(priority: usize) -> char {
+    match priority {
+        1..=26 => (b'a' + priority as u8 - 1) as char,
+        _ => (b'A' + priority as u8 - 27) as char,
+    }
+}
+
+/// A set of item types, bit `p` is set if the item with priority `p` is in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Items(u64);
+
+impl Items {
+    /// every item type there is
+    pub const ALL: Items = Items(((1 << 52) - 1) << 1);
+
+    /// the items in `s`, characters that are not items are ignored
+    pub fn new(s: &str) -> Items {
+        Items(
+            s.chars()
+                .filter_map(priority)
+                .fold(0, |mask, p| mask | 1 << p),
+        )
+    }
+
+    pub fn intersection(self, other: Items) -> Items {
+        Items(self.0 & other.0)
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn priorities(&self) -> impl Iterator<Item = usize> {
+        let mask = self.0;
+        (1..=52).filter(move |p| mask & 1 << p != 0)
+    }
+}
+
+impl fmt::Display for Items {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.priorities().map(item).collect::<String>())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rucksack {
+    /// 0-based line of the input
+    pub index: usize,
+    pub items: String,
+}
+
+impl Rucksack {
+    pub fn all_items(&self) -> Items {
+        Items::new(&self.items)
+    }
+
+    /// the items of each of the `n` equally sized compartments
+    pub fn compartments(&self, n: usize) -> Result<Vec<Items>> {
+        if n == 0 || !self.items.len().is_multiple_of(n) {
+            return Err(Error::line(
+                self.index,
+                format!(
+                    "{} items do not fit into {} equal compartments",
+                    self.items.len(),
+                    n
+                ),
+            ));
         }
+        let size = self.items.len() / n;
+        Ok((0..n)
+            .map(|c| Items::new(&self.items[c * size..(c + 1) * size]))
+            .collect())
     }
+}
 
-    println!("score: {}", score);
+/// the priority of the single item in all `sets`, `what` and `index` describe them for errors
+fn common_priority(sets: &[Items], what: &str, index: usize) -> Result<usize> {
+    let common = sets.iter().fold(Items::ALL, |c, s| c.intersection(*s));
+    match common.len() {
+        1 => Ok(common.priorities().next().unwrap()),
+        0 => Err(Error::line(
+            index,
+            format!("{} have no item in common", what),
+        )),
+        _ => Err(Error::line(
+            index,
+            format!("{} have several items in common: {}", what, common),
+        )),
+    }
 }
 
-fn aoc03_2() {
-    println!("solving AOC day 3 part 2");
-    let reader = BufReader::new(File::open("input-03").unwrap());
-    let mut score: usize = 0;
-    let mut collection = vec![];
+/// sum of the priorities of the items found in all `compartments` of a rucksack
+pub fn compartment_priorities(rucksacks: &[Rucksack], compartments: usize) -> Result<usize> {
+    let mut score = 0;
+    for rucksack in rucksacks.iter() {
+        score += common_priority(
+            &rucksack.compartments(compartments)?,
+            "the compartments",
+            rucksack.index,
+        )?;
+    }
+    Ok(score)
+}
 
-    for (_index, line) in reader.lines().enumerate() {
-        let line = line.unwrap();
-        collection.push(line);
+/// sum of the priorities of the badges, the items carried by every elve of a group
+pub fn group_priorities(rucksacks: &[Rucksack], group_size: usize) -> Result<usize> {
+    if group_size == 0 || !rucksacks.len().is_multiple_of(group_size) {
+        return Err(Error::no_solution(format!(
+            "{} rucksacks cannot be split into groups of {}",
+            rucksacks.len(),
+            group_size
+        )));
+    }
+    let mut score = 0;
+    for group in rucksacks.chunks(group_size) {
+        let items: Vec<Items> = group.iter().map(Rucksack::all_items).collect();
+        score += common_priority(&items, "the rucksacks of the group", group[0].index)?;
+    }
+    Ok(score)
+}
 
-        if collection.len() == 3 {
-            for c in LETTERS.chars() {
-                let mut found = true;
-                for line in collection.iter() {
-                    if line.find(c) == None {
-                        found = false;
-                        break;
-                    }
-                }
+pub struct Aoc03;
 
-                if found {
-                    score += LETTERS.find(c).unwrap() + 1;
-                    break;
-                }
-            }
+impl Solver for Aoc03 {
+    type Input = Vec<Rucksack>;
 
-            collection.clear();
+    fn parse(&self, input: &str) -> Result<Vec<Rucksack>> {
+        let mut rucksacks = vec![];
+        for (index, line) in input.lines().enumerate() {
+            if let Some(column) = line.find(|c| priority(c).is_none()) {
+                return Err(Error::parse(index + 1, column + 1, "items must be letters"));
+            }
+            rucksacks.push(Rucksack {
+                index,
+                items: line.to_string(),
+            });
         }
+        Ok(rucksacks)
+    }
+
+    fn part1(&self, rucksacks: &Vec<Rucksack>) -> Result<Answer> {
+        Ok(compartment_priorities(rucksacks, 2)?.into())
     }
 
-    println!("score: {}", score);
+    fn part2(&self, rucksacks: &Vec<Rucksack>) -> Result<Answer> {
+        Ok(group_priorities(rucksacks, GROUP_SIZE)?.into())
+    }
 }
 
-pub fn aoc03() {
-    aoc03_1();
-    aoc03_2();
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+vJrwpWtwJgWrhcsFMMfFFhFp
+jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
+PmmdzqPrVvPwwTWBwg
+wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
+ttgJtRGJQctTZtZT
+CrZsJsPPZsGzwwsLwLmpwMDw
+";
+
+    #[test]
+    fn example() {
+        let rucksacks = Aoc03.parse(EXAMPLE).unwrap();
+        assert_eq!(Aoc03.part1(&rucksacks).unwrap(), Answer::from(157));
+        assert_eq!(Aoc03.part2(&rucksacks).unwrap(), Answer::from(70));
+    }
+
+    #[test]
+    fn items() {
+        assert_eq!(priority('p'), Some(16));
+        assert_eq!(priority('L'), Some(38));
+        assert_eq!(priority('1'), None);
+        let items = Items::new("vJrwpWtwJgWr");
+        assert_eq!(items.len(), 8);
+        assert_eq!(items.to_string(), "gprtvwJW");
+        assert_eq!(Items::ALL.len(), 52);
+    }
+
+    #[test]
+    fn configurable_groups() {
+        let rucksacks = Aoc03.parse(EXAMPLE).unwrap();
+        // every rucksack is its own group, so all its items are in common
+        assert!(matches!(
+            group_priorities(&rucksacks, 1),
+            Err(Error::Parse { line: 1, .. })
+        ));
+        assert!(group_priorities(&rucksacks, 4).is_err());
+        assert_eq!(group_priorities(&rucksacks[..3], 3).unwrap(), 18);
+
+        let rucksacks = Aoc03.parse("aBaCaDaEaB\n").unwrap();
+        assert_eq!(compartment_priorities(&rucksacks, 5).unwrap(), 1);
+        assert!(compartment_priorities(&rucksacks, 3).is_err());
+        assert!(matches!(
+            compartment_priorities(&rucksacks, 2),
+            Err(Error::Parse { line: 1, .. })
+        ));
+    }
 }
@@ -1,95 +1,184 @@
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-use parse_int::parse;
-use min_max::*;
-
-fn splitter(s: &str, c: char) -> Vec<&str> {
-  let parts = s.split(c).collect::<Vec<&str>>();
-  if parts.len() != 2 {
-    panic!("cannot split {} using {}", s, c);
-  }
-  parts
+use crate::error::{self, Error, Result};
+use crate::interval::{Interval, IntervalSet};
+use crate::solver::{Answer, Solver};
+
+/// splits `s`, found at `offset` in the line with `index`, into two parts
+fn splitter(s: &str, c: char, index: usize, offset: usize) -> Result<Vec<&str>> {
+    let parts = s.split(c).collect::<Vec<&str>>();
+    if parts.len() != 2 {
+        return Err(Error::parse(
+            index + 1,
+            offset + 1,
+            format!("cannot split {:?} using {:?}", s, c),
+        ));
+    }
+    Ok(parts)
+}
+
+/// the sections of one elve, like `2-4`
+fn sections(s: &str, index: usize, offset: usize) -> Result<Interval> {
+    let parts = splitter(s, '-', index, offset)?;
+    let start = error::number::<isize>(parts[0], index, offset)?;
+    let end = error::number::<isize>(parts[1], index, offset + parts[0].len() + 1)?;
+    if start > end {
+        return Err(Error::parse(
+            index + 1,
+            offset + 1,
+            format!("sections {:?} end before they start", s),
+        ));
+    }
+    Ok(Interval::new(start, end))
 }
 
-struct Elve {
-  min: u32,
-  max: u32,
+/// The section assignments of a group of elves, one line of the input.
+pub struct ElveGroup {
+    pub elves: Vec<Interval>,
 }
 
-impl Elve {
-    fn new(s: &str) -> Elve {
-      let parts = splitter(s, '-');
-      let start = parse::<u32>(&parts.get(0).unwrap()).unwrap();
-      let end = parse::<u32>(&parts.get(1).unwrap()).unwrap();
-      Elve {
-        min: min!(start,end),
-        max: max!(start, end),
-      }
+impl ElveGroup {
+    fn new(s: &str, index: usize) -> Result<ElveGroup> {
+        let mut elves = vec![];
+        let mut offset = 0;
+        for part in s.split(',') {
+            elves.push(sections(part, index, offset)?);
+            offset += part.len() + 1;
+        }
+        Ok(ElveGroup { elves })
     }
 
-    fn fully_contains(&self, other: &Elve) -> bool {
-       self.min >= other.min && self.max <= other.max
+    /// all pairs of different elves
+    fn pairs(&self) -> impl Iterator<Item = (&Interval, &Interval)> {
+        self.elves
+            .iter()
+            .enumerate()
+            .flat_map(|(i, a)| self.elves[i + 1..].iter().map(move |b| (a, b)))
     }
 
-    fn overlaps(&self, other: &Elve) -> bool {
-      let other_first = self.min <= other.max && self.max >= other.min;
-      let this_first = other.min <= self.max && other.max >= self.min;
-      other_first || this_first
+    /// true if some elve only got sections another elve got as well
+    pub fn fully_contained(&self) -> bool {
+        self.pairs()
+            .any(|(a, b)| a.contains_interval(b) || b.contains_interval(a))
     }
-}
 
+    pub fn overlaps(&self) -> bool {
+        self.pairs().any(|(a, b)| a.overlaps(b))
+    }
 
-struct ElvePair {
-  first: Elve,
-  last: Elve,
-}
+    /// number of sections claimed by more than one elve, each counted once
+    pub fn overlap(&self) -> usize {
+        self.contested().len()
+    }
 
-impl ElvePair {
-    fn new(s: &str) -> ElvePair {
-      let parts = splitter(s, ',');
-      ElvePair { first: Elve::new(parts.get(0).unwrap()), last: Elve::new(parts.get(1).unwrap()) }
+    /// from the first to the last section assigned to anyone
+    pub fn span(&self) -> Option<Interval> {
+        self.covered().span()
     }
-}
 
+    /// sections assigned to at least one elve
+    pub fn covered(&self) -> IntervalSet {
+        self.elves.iter().copied().collect()
+    }
 
-fn aoc04_1 (elve_pairs: &Vec<ElvePair>) {
-  println!("solving AOC day 4 part 1");
-  let mut score: usize = 0;
+    /// sections within the span nobody has to clean
+    pub fn uncovered(&self) -> IntervalSet {
+        match self.span() {
+            Some(span) => self.covered().gaps(span),
+            None => IntervalSet::new(),
+        }
+    }
 
-  for elve_pair in elve_pairs.iter() {
-    if elve_pair.first.fully_contains(&elve_pair.last) || elve_pair.last.fully_contains(&elve_pair.first) {
-      score+=1;
+    /// sections claimed by more than one elve
+    pub fn contested(&self) -> IntervalSet {
+        self.pairs()
+            .filter_map(|(a, b)| a.intersection(b))
+            .collect()
     }
-  }
+}
 
-  println!("score: {}", score);
+fn aoc04_1(groups: &[ElveGroup]) -> usize {
+    groups.iter().filter(|g| g.fully_contained()).count()
+}
 
+fn aoc04_2(groups: &[ElveGroup]) -> usize {
+    groups.iter().filter(|g| g.overlaps()).count()
 }
 
-fn aoc04_2 (elve_pairs: &Vec<ElvePair>)  {
-  println!("solving AOC day 4 part 2");
-  let mut score: usize = 0;
+pub struct Aoc04;
+
+impl Solver for Aoc04 {
+    type Input = Vec<ElveGroup>;
 
-  for elve_pair in elve_pairs.iter() {
-    if elve_pair.first.overlaps(&elve_pair.last) {
-      score+=1;
+    fn parse(&self, input: &str) -> Result<Vec<ElveGroup>> {
+        input
+            .lines()
+            .enumerate()
+            .map(|(index, line)| ElveGroup::new(line, index))
+            .collect()
     }
-  }
 
-  println!("score: {}", score);
+    fn part1(&self, groups: &Vec<ElveGroup>) -> Result<Answer> {
+        Ok(aoc04_1(groups).into())
+    }
 
+    fn part2(&self, groups: &Vec<ElveGroup>) -> Result<Answer> {
+        Ok(aoc04_2(groups).into())
+    }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+2-4,6-8
+2-3,4-5
+5-7,7-9
+2-8,3-7
+6-6,4-6
+2-6,4-8
+";
+
+    #[test]
+    fn example() {
+        let groups = Aoc04.parse(EXAMPLE).unwrap();
+        assert_eq!(Aoc04.part1(&groups).unwrap(), Answer::from(2));
+        assert_eq!(Aoc04.part2(&groups).unwrap(), Answer::from(4));
+    }
 
-pub fn aoc04() {
-    let reader = BufReader::new(File::open("input-04").unwrap());
-
-    let mut elve_pairs: Vec<ElvePair> = vec![];
-
-    for (_index, line) in reader.lines().enumerate() {
-        elve_pairs.push(ElvePair::new(&line.unwrap()));
+    #[test]
+    fn groups() {
+        let groups = Aoc04.parse("1-3,5-9,2-6\n4-4\n1-2,5-6,3-3\n").unwrap();
+        let group = &groups[0];
+        assert!(group.overlaps() && !group.fully_contained());
+        assert_eq!(group.overlap(), 2 + 2);
+        assert_eq!(group.span(), Some(Interval::new(1, 9)));
+        assert_eq!(group.uncovered(), IntervalSet::new());
+        assert_eq!(
+            group.contested(),
+            IntervalSet::from_iter([Interval::new(2, 3), Interval::new(5, 6)])
+        );
+
+        assert!(!groups[1].overlaps());
+        assert_eq!(groups[1].overlap(), 0);
+        assert_eq!(
+            groups[2].uncovered(),
+            IntervalSet::from_iter([Interval::new(4, 4)])
+        );
+        assert!(Aoc04.parse("1-2,3").is_err());
+        match Aoc04.parse("2-4,6-8\n1-3,4-2\n") {
+            Err(Error::Parse { line, column, .. }) => assert_eq!((line, column), (2, 5)),
+            other => panic!("expected a parse error, got {:?}", other.map(|_| ())),
+        }
     }
 
-  aoc04_1(&elve_pairs);
-  aoc04_2(&elve_pairs);
-}
\ No newline at end of file
+    #[test]
+    fn same_sections() {
+        let groups = Aoc04.parse("1-5,1-5,1-5\n").unwrap();
+        assert!(groups[0].fully_contained());
+        assert_eq!(groups[0].overlap(), 5);
+        assert_eq!(
+            groups[0].contested(),
+            IntervalSet::from_iter([Interval::new(1, 5)])
+        );
+    }
+}
@@ -1,126 +1,485 @@
-use parse_int::parse;
 use regex::Regex;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-use std::sync::Mutex;
+use std::fmt;
 
-#[derive(Debug, Clone)]
-struct Stack {
-    chars: Vec<char>,
+use crate::error::{self, Error, Result};
+use crate::input::{self, Record};
+use crate::solver::{Answer, Solver};
+
+/// The crane model decides how many crates are lifted at once.
+///
+/// Crates lifted together keep their order, so a crane moving one crate at
+/// a time reverses them while one that moves them all at once does not.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Crane {
+    /// CrateMover 9000
+    OneAtATime,
+    /// CrateMover 9001
+    AllAtOnce,
+    /// lifts up to the given number of crates at once
+    Capacity(usize),
+}
+
+impl Crane {
+    pub fn capacity(&self) -> usize {
+        match self {
+            Crane::OneAtATime => 1,
+            Crane::AllAtOnce => usize::MAX,
+            Crane::Capacity(k) => (*k).max(1),
+        }
+    }
+}
+
+/// the labelled stacks of crates, bottom crate first
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stacks {
+    labels: Vec<String>,
+    stacks: Vec<Vec<char>>,
 }
 
-impl Stack {
-    fn add_char(&mut self, c: char) {
-        self.chars.push(c);
+impl Stacks {
+    /// Parses the drawing of the stacks, the last line holds the labels.
+    ///
+    /// Every stack takes four columns, `[X]` and a separating space, and the
+    /// stacks may be as high and as many as they like.
+    pub fn parse(drawing: &Record) -> Result<Stacks> {
+        let (label_line, crate_lines) = drawing
+            .lines
+            .split_last()
+            .ok_or_else(|| Error::line(drawing.index, "missing stack drawing"))?;
+        let label_index = drawing.index + crate_lines.len();
+
+        let labels: Vec<String> = label_line.split_whitespace().map(String::from).collect();
+        if labels.is_empty() {
+            return Err(Error::line(label_index, "missing stack labels"));
+        }
+        for (i, label) in labels.iter().enumerate() {
+            if labels[..i].contains(label) {
+                return Err(Error::line(
+                    label_index,
+                    format!("stack {:?} is labelled twice", label),
+                ));
+            }
+        }
+
+        let mut stacks = vec![vec![]; labels.len()];
+        for (row, line) in crate_lines.iter().enumerate().rev() {
+            let index = drawing.index + row;
+            let chars: Vec<char> = line.chars().collect();
+            for (stack, cell) in chars.chunks(4).enumerate() {
+                match cell {
+                    ['[', c, ']'] | ['[', c, ']', ' '] if *c != ' ' => {
+                        if stack >= labels.len() {
+                            return Err(Error::parse(
+                                index + 1,
+                                stack * 4 + 1,
+                                format!("crate {} is not on a labelled stack", c),
+                            ));
+                        }
+                        if stacks[stack].len() < crate_lines.len() - 1 - row {
+                            return Err(Error::parse(
+                                index + 1,
+                                stack * 4 + 1,
+                                format!("crate {} is floating in the air", c),
+                            ));
+                        }
+                        stacks[stack].push(*c);
+                    }
+                    cell if cell.iter().all(|c| *c == ' ') => (),
+                    _ => {
+                        return Err(Error::parse(
+                            index + 1,
+                            stack * 4 + 1,
+                            format!("expected a crate, found {:?}", String::from_iter(cell)),
+                        ))
+                    }
+                }
+            }
+        }
+
+        Ok(Stacks { labels, stacks })
+    }
+
+    pub fn labels(&self) -> &[String] {
+        &self.labels
+    }
+
+    pub fn stack(&self, index: usize) -> &[char] {
+        &self.stacks[index]
+    }
+
+    /// index of the stack with the `label`
+    pub fn position(&self, label: &str) -> Option<usize> {
+        self.labels.iter().position(|l| l == label)
     }
-    fn resort(&mut self) {
-        self.chars.reverse();
+
+    /// the top crate of every stack that is not empty
+    pub fn top_crates(&self) -> String {
+        self.stacks.iter().filter_map(|s| s.last()).collect()
     }
-    fn pop_many(&mut self, num: usize) -> Vec<char> {
-        let l = self.chars.len();
-        self.chars.splice((l - num)..(l), []).collect()
+
+    /// takes the top `num` crates off the stack with the `index`, keeping their order
+    pub fn pop_many(&mut self, index: usize, num: usize) -> Result<Vec<char>> {
+        let stack = &mut self.stacks[index];
+        if num > stack.len() {
+            return Err(Error::invalid(format!(
+                "cannot take {} crates from stack {}, which holds {}",
+                num,
+                self.labels[index],
+                stack.len()
+            )));
+        }
+        Ok(stack.split_off(stack.len() - num))
+    }
+
+    /// lets the `crane` execute the `action`
+    pub fn apply(&mut self, crane: Crane, action: &Action) -> Result<()> {
+        if action.num > self.stacks[action.from].len() {
+            // fail before the crane moves anything
+            self.pop_many(action.from, action.num)?;
+        }
+        let mut remaining = action.num;
+        while remaining > 0 {
+            let lift = remaining.min(crane.capacity());
+            let mut crates = self.pop_many(action.from, lift)?;
+            self.stacks[action.to].append(&mut crates);
+            remaining -= lift;
+        }
+        Ok(())
+    }
+
+    /// Undoes an `action` the `crane` executed before.
+    ///
+    /// The crane lifted full loads first and the remainder last, so the
+    /// remainder goes back first.
+    pub fn revert(&mut self, crane: Crane, action: &Action) -> Result<()> {
+        let capacity = crane.capacity();
+        let mut lifts = vec![];
+        if !action.num.is_multiple_of(capacity) {
+            lifts.push(action.num % capacity);
+        }
+        lifts.extend(std::iter::repeat_n(capacity, action.num / capacity));
+
+        if action.num > self.stacks[action.to].len() {
+            self.pop_many(action.to, action.num)?;
+        }
+        for lift in lifts {
+            let mut crates = self.pop_many(action.to, lift)?;
+            self.stacks[action.from].append(&mut crates);
+        }
+        Ok(())
+    }
+}
+
+/// draws the stacks just like the puzzle input
+impl fmt::Display for Stacks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let height = self.stacks.iter().map(Vec::len).max().unwrap_or(0);
+        for level in (0..height).rev() {
+            let row: Vec<String> = self
+                .stacks
+                .iter()
+                .map(|s| match s.get(level) {
+                    Some(c) => format!("[{}]", c),
+                    None => "   ".to_string(),
+                })
+                .collect();
+            writeln!(f, "{}", row.join(" "))?;
+        }
+        let labels: Vec<String> = self.labels.iter().map(|l| format!("{:^3}", l)).collect();
+        write!(f, "{}", labels.join(" "))
     }
 }
 
-struct Action {
-    num: usize,
-    from: usize,
-    to: usize,
+/// moves `num` crates from the stack with index `from` to the one with index `to`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Action {
+    pub num: usize,
+    pub from: usize,
+    pub to: usize,
 }
 
 impl Action {
-    fn new(line: &str) -> Action {
-        let re = Regex::new(r"^move (\d+) from (\d+) to (\d+)$").unwrap();
-        for cap in re.captures_iter(&line) {
-            let num = parse::<usize>(&cap[1]).unwrap();
-            let from = parse::<usize>(&cap[2]).unwrap();
-            let to = parse::<usize>(&cap[3]).unwrap();
-            return Action {
-                num,
-                from: from - 1,
-                to: to - 1,
-            };
+    fn new(line: &str, index: usize, stacks: &Stacks) -> Result<Action> {
+        let re = Regex::new(r"^move (\d+) from (\S+) to (\S+)$").unwrap();
+        let cap = re
+            .captures(line)
+            .ok_or_else(|| Error::line(index, format!("invalid move {:?}", line)))?;
+
+        let num = cap.get(1).unwrap();
+        let num = error::number::<usize>(num.as_str(), index, num.start())?;
+        let mut positions = vec![];
+        for group in 2..=3 {
+            let label = cap.get(group).unwrap();
+            positions.push(stacks.position(label.as_str()).ok_or_else(|| {
+                Error::parse(
+                    index + 1,
+                    label.start() + 1,
+                    format!("there is no stack {}", label.as_str()),
+                )
+            })?);
         }
-        panic!("failed to parse");
+
+        Ok(Action {
+            num,
+            from: positions[0],
+            to: positions[1],
+        })
     }
 }
 
-fn aoc05_1(stacks: &mut Vec<Mutex<Stack>>, actions: &Vec<Action>) {
-    println!("solving AOC day 5 part 1");
-    for action in actions.iter() {
-        let mut num = action.num;
+pub struct Cargo {
+    pub stacks: Stacks,
+    pub actions: Vec<Action>,
+}
+
+impl Cargo {
+    /// runs all actions with the `crane` and returns the final stacks
+    pub fn run(&self, crane: Crane) -> Result<Stacks> {
+        self.run_with(crane, |_, _| ())
+    }
 
-        let mut stack_from = stacks.get(action.from).unwrap().lock().unwrap();
-        let mut stack_to = stacks.get(action.to).unwrap().lock().unwrap();
-        while num > 0 {
-            stack_to.add_char(stack_from.chars.pop().unwrap());
-            num -= 1;
+    /// like [`Cargo::run`], but shows the stacks to `inspect` after every action
+    pub fn run_with(
+        &self,
+        crane: Crane,
+        mut inspect: impl FnMut(&Action, &Stacks),
+    ) -> Result<Stacks> {
+        let mut replay = self.replay(crane);
+        while let Some(action) = replay.step()? {
+            inspect(action, replay.stacks());
         }
+        Ok(replay.stacks)
     }
-    for s in stacks.iter() {
-        print!("{}", s.lock().unwrap().chars.last().unwrap());
+
+    /// a replay of the actions that starts before the first move
+    pub fn replay(&self, crane: Crane) -> Replay<'_> {
+        Replay {
+            cargo: self,
+            crane,
+            stacks: self.stacks.clone(),
+            done: 0,
+        }
     }
-    println!();
 }
 
-fn aoc05_2(stacks: &mut Vec<Mutex<Stack>>, actions: &Vec<Action>) {
-    println!("solving AOC day 5 part 2");
-    for action in actions.iter() {
-        let mut stack_from = stacks.get(action.from).unwrap().lock().unwrap();
-        let mut stack_to = stacks.get(action.to).unwrap().lock().unwrap();
-        let mut u: Vec<_> = stack_from.pop_many(action.num);
-        stack_to.chars.append(&mut u);
+/// Steps through the actions of a [`Cargo`] in both directions.
+pub struct Replay<'a> {
+    cargo: &'a Cargo,
+    crane: Crane,
+    stacks: Stacks,
+    done: usize,
+}
+
+impl<'a> Replay<'a> {
+    /// the stacks after [`Replay::position`] moves
+    pub fn stacks(&self) -> &Stacks {
+        &self.stacks
     }
-    for s in stacks.iter() {
-        print!("{}", s.lock().unwrap().chars.last().unwrap());
+
+    /// number of moves executed so far
+    pub fn position(&self) -> usize {
+        self.done
+    }
+
+    /// number of moves in total
+    pub fn len(&self) -> usize {
+        self.cargo.actions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cargo.actions.is_empty()
+    }
+
+    /// executes the next move, `None` once all moves are done
+    pub fn step(&mut self) -> Result<Option<&'a Action>> {
+        let Some(action) = self.cargo.actions.get(self.done) else {
+            return Ok(None);
+        };
+        self.stacks.apply(self.crane, action)?;
+        self.done += 1;
+        Ok(Some(action))
+    }
+
+    /// takes back the last move, `None` before the first move
+    pub fn undo(&mut self) -> Result<Option<&'a Action>> {
+        if self.done == 0 {
+            return Ok(None);
+        }
+        let action = &self.cargo.actions[self.done - 1];
+        self.stacks.revert(self.crane, action)?;
+        self.done -= 1;
+        Ok(Some(action))
+    }
+
+    /// steps forward or backward until exactly `n` moves are done
+    pub fn jump(&mut self, n: usize) -> Result<&Stacks> {
+        if n > self.len() {
+            return Err(Error::invalid(format!(
+                "there are only {} moves, cannot jump to move {}",
+                self.len(),
+                n
+            )));
+        }
+        while self.done < n {
+            self.step()?;
+        }
+        while self.done > n {
+            self.undo()?;
+        }
+        Ok(&self.stacks)
     }
-    println!();
 }
 
-pub fn aoc05() {
-    let reader = BufReader::new(File::open("input-05").unwrap());
-
-    let mut stacks: Vec<Mutex<Stack>> = vec![];
-    let mut actions: Vec<Action> = vec![];
-    let mut init_done = false;
-
-    for (_index, line) in reader.lines().enumerate() {
-        let line = line.unwrap();
-        if line == "" {
-            continue;
-        }
-        if !init_done {
-            let mut stack_number = 0;
-            while (stack_number * 4) + 1 < line.len() && !init_done {
-                match line.chars().nth((stack_number * 4) + 1).unwrap() {
-                    '1' => {
-                        init_done = true;
-                        for s in stacks.iter() {
-                            s.lock().unwrap().resort();
-                        }
-                        break;
-                    }
-                    ' ' => (),
-                    x => {
-                        while stacks.len() < stack_number + 1 {
-                            stacks.push(Mutex::new(Stack { chars: vec![] }));
-                        }
-                        let mut stack = stacks.get(stack_number).unwrap().lock().unwrap();
-                        stack.add_char(x);
-                    }
-                }
-                stack_number += 1;
+pub struct Aoc05;
+
+impl Solver for Aoc05 {
+    type Input = Cargo;
+
+    fn parse(&self, input: &str) -> Result<Cargo> {
+        let records: Vec<Record> = input::split_records(input).collect();
+        let (drawing, moves) = records
+            .split_first()
+            .ok_or_else(|| Error::line(0, "missing stack drawing"))?;
+        let stacks = Stacks::parse(drawing)?;
+
+        let mut actions: Vec<Action> = vec![];
+        // the heights do not depend on the crane, so every move can be checked right away
+        let mut heights: Vec<usize> = (0..stacks.labels().len())
+            .map(|i| stacks.stack(i).len())
+            .collect();
+        for (index, line) in moves.iter().flat_map(Record::lines) {
+            let action = Action::new(line, index, &stacks)?;
+            if heights[action.from] < action.num {
+                return Err(Error::line(
+                    index,
+                    format!(
+                        "cannot move {} crates from stack {}, which holds {}",
+                        action.num,
+                        stacks.labels()[action.from],
+                        heights[action.from]
+                    ),
+                ));
             }
-        } else {
-            actions.push(Action::new(&line))
+            heights[action.from] -= action.num;
+            heights[action.to] += action.num;
+            actions.push(action);
         }
+
+        Ok(Cargo { stacks, actions })
+    }
+
+    fn part1(&self, cargo: &Cargo) -> Result<Answer> {
+        Ok(cargo.run(Crane::OneAtATime)?.top_crates().into())
+    }
+
+    fn part2(&self, cargo: &Cargo) -> Result<Answer> {
+        Ok(cargo.run(Crane::AllAtOnce)?.top_crates().into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = concat!(
+        "    [D]    \n",
+        "[N] [C]    \n",
+        "[Z] [M] [P]\n",
+        " 1   2   3 \n",
+        "\n",
+        "move 1 from 2 to 1\n",
+        "move 3 from 1 to 3\n",
+        "move 2 from 2 to 1\n",
+        "move 1 from 1 to 2\n",
+    );
+
+    #[test]
+    fn example() {
+        let cargo = Aoc05.parse(EXAMPLE).unwrap();
+        assert_eq!(Aoc05.part1(&cargo).unwrap(), Answer::from("CMZ"));
+        assert_eq!(Aoc05.part2(&cargo).unwrap(), Answer::from("MCD"));
     }
 
-    let mut stacks2 = stacks
-        .iter()
-        .map(|s| Mutex::new(s.lock().unwrap().clone()))
-        .collect::<Vec<_>>();
+    #[test]
+    fn cranes() {
+        let cargo = Aoc05.parse(EXAMPLE).unwrap();
+        assert_eq!(cargo.run(Crane::Capacity(1)).unwrap().top_crates(), "CMZ");
+        assert_eq!(cargo.run(Crane::Capacity(3)).unwrap().top_crates(), "MCD");
+        // the second move lifts 2 and then 1 crate
+        assert_eq!(cargo.run(Crane::Capacity(2)).unwrap().top_crates(), "MCZ");
+    }
+
+    #[test]
+    fn drawing() {
+        let cargo = Aoc05.parse(EXAMPLE).unwrap();
+        assert_eq!(
+            format!("{}\n", cargo.stacks),
+            EXAMPLE.split("\n\n").next().unwrap().to_string() + "\n"
+        );
+
+        let mut drawings = vec![];
+        cargo
+            .run_with(Crane::OneAtATime, |_, stacks| {
+                drawings.push(stacks.to_string())
+            })
+            .unwrap();
+        assert_eq!(
+            drawings[0],
+            "[D]        \n[N] [C]    \n[Z] [M] [P]\n 1   2   3 "
+        );
+    }
+
+    #[test]
+    fn labels_and_width() {
+        let cargo = Aoc05
+            .parse("[A]         [E]\n[B]     [D] [F]\n a   b   c   d \n\nmove 2 from a to b\n")
+            .unwrap();
+        assert_eq!(cargo.stacks.labels(), ["a", "b", "c", "d"]);
+        assert_eq!(cargo.run(Crane::OneAtATime).unwrap().top_crates(), "BDE");
+        assert!(Aoc05.parse("[A]\n 1 \n\nmove 1 from 1 to 2\n").is_err());
+        assert!(Aoc05.parse("[A] [B]\n 1 \n").is_err());
+        assert!(Aoc05.parse("[A]\n    \n 1 \n").is_err());
+    }
 
-    aoc05_1(&mut stacks, &actions);
-    aoc05_2(&mut stacks2, &actions);
+    #[test]
+    fn replay() {
+        let cargo = Aoc05.parse(EXAMPLE).unwrap();
+        for crane in [Crane::OneAtATime, Crane::AllAtOnce, Crane::Capacity(2)] {
+            let mut states = vec![cargo.stacks.clone()];
+            cargo
+                .run_with(crane, |_, stacks| states.push(stacks.clone()))
+                .unwrap();
+
+            let mut replay = cargo.replay(crane);
+            assert_eq!(replay.undo().unwrap(), None);
+            assert_eq!(replay.jump(2).unwrap(), &states[2]);
+            assert_eq!(replay.undo().unwrap(), Some(&cargo.actions[1]));
+            assert_eq!(replay.stacks(), &states[1]);
+            assert_eq!(replay.jump(4).unwrap(), &cargo.run(crane).unwrap());
+            assert_eq!(replay.step().unwrap(), None);
+            assert_eq!(replay.undo().unwrap(), Some(&cargo.actions[3]));
+            assert_eq!(replay.position(), 3);
+            assert_eq!(replay.jump(0).unwrap(), &cargo.stacks);
+            assert!(matches!(replay.jump(5), Err(Error::Invalid(_))));
+        }
+    }
+
+    #[test]
+    fn underflow() {
+        match Aoc05.parse("[A]\n 1   2 \n\nmove 1 from 1 to 2\nmove 2 from 2 to 1\n") {
+            Err(Error::Parse { line, .. }) => assert_eq!(line, 5),
+            other => panic!("expected a parse error, got {:?}", other.map(|_| ())),
+        }
+        let mut cargo = Aoc05.parse("[A]\n 1   2 \n").unwrap();
+        cargo.actions.push(Action {
+            num: 2,
+            from: 0,
+            to: 1,
+        });
+        assert!(matches!(
+            cargo.run(Crane::OneAtATime),
+            Err(Error::Invalid(_))
+        ));
+        assert_eq!(cargo.stacks.pop_many(0, 1).unwrap(), ['A']);
+    }
 }
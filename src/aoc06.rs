@@ -1,39 +1,192 @@
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-
-fn find_unique_slice(lines: &Vec<String>, slice_len: usize) {
-    for line in lines {
-        for i in (slice_len - 1)..line.len() {
-            let mut slice: Vec<char> = line[(i - (slice_len - 1))..=i].chars().map(|c| c).collect();
-            slice.sort();
-            slice.dedup();
-            if slice.len() == slice_len {
-                println!("found {:?}, continue at {}", slice, i + 1);
-                break;
+use std::io::{self, BufReader, Bytes, Read};
+
+use crate::error::{Error, Result};
+use crate::solver::{Answer, Solver};
+
+/// Finds windows of `len` different bytes in a stream, one byte at a time.
+///
+/// The last `len` bytes live in a ring buffer and a count per byte value
+/// tracks how many of them are distinct, so every byte costs O(1) no matter
+/// how long the stream or the window is.
+#[derive(Debug, Clone)]
+pub struct MarkerDetector {
+    window: Vec<u8>,
+    counts: [usize; 256],
+    distinct: usize,
+    position: usize,
+}
+
+impl MarkerDetector {
+    pub fn new(len: usize) -> MarkerDetector {
+        assert!(len > 0, "a marker needs at least one character");
+        MarkerDetector {
+            window: vec![0; len],
+            counts: [0; 256],
+            distinct: 0,
+            position: 0,
+        }
+    }
+
+    /// number of bytes pushed so far
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// adds the next byte, true if the last `len` bytes are all different now
+    pub fn push(&mut self, byte: u8) -> bool {
+        let len = self.window.len();
+        let slot = self.position % len;
+        if self.position >= len {
+            let old = self.window[slot] as usize;
+            self.counts[old] -= 1;
+            if self.counts[old] == 0 {
+                self.distinct -= 1;
             }
         }
+        self.window[slot] = byte;
+        self.counts[byte as usize] += 1;
+        if self.counts[byte as usize] == 1 {
+            self.distinct += 1;
+        }
+        self.position += 1;
+        self.distinct == len
     }
 }
 
-fn aoc06_1(lines: &Vec<String>) {
-    println!("solving AOC day 6 part 1");
-    find_unique_slice(&lines, 4);
+/// Iterator over the end positions of all markers in a stream.
+///
+/// Whitespace, such as the trailing newline of the puzzle input, is skipped
+/// and does not count as a character.
+pub struct Markers<R: Read> {
+    bytes: Bytes<BufReader<R>>,
+    detector: MarkerDetector,
 }
 
-fn aoc06_2(lines: &Vec<String>) {
-    println!("solving AOC day 6 part 2");
-    find_unique_slice(&lines, 14);
+impl<R: Read> Iterator for Markers<R> {
+    type Item = io::Result<usize>;
+
+    fn next(&mut self) -> Option<io::Result<usize>> {
+        for byte in self.bytes.by_ref() {
+            match byte {
+                Err(e) => return Some(Err(e)),
+                Ok(b) if b.is_ascii_whitespace() => (),
+                Ok(b) => {
+                    if self.detector.push(b) {
+                        return Some(Ok(self.detector.position()));
+                    }
+                }
+            }
+        }
+        None
+    }
 }
 
-pub fn aoc06() {
-    let reader = BufReader::new(File::open("input-06").unwrap());
-    let mut lines: Vec<String> = vec![];
+/// all markers of `len` different characters in the `reader`
+pub fn markers<R: Read>(reader: R, len: usize) -> Markers<R> {
+    Markers {
+        bytes: BufReader::new(reader).bytes(),
+        detector: MarkerDetector::new(len),
+    }
+}
 
-    for (_index, line) in reader.lines().enumerate() {
-        let line = line.unwrap();
-        lines.push(line.clone());
+/// number of characters read until the last `slice_len` characters were all different
+pub fn find_unique_slice(line: &str, slice_len: usize) -> Result<usize> {
+    if slice_len > 0 {
+        if let Some(position) = markers(line.as_bytes(), slice_len).next() {
+            // reading from a slice cannot fail
+            return Ok(position.unwrap());
+        }
     }
+    Err(Error::no_solution(format!(
+        "no marker of length {} found",
+        slice_len
+    )))
+}
+
+fn aoc06_1(line: &str) -> Result<usize> {
+    find_unique_slice(line, 4)
+}
+
+fn aoc06_2(line: &str) -> Result<usize> {
+    find_unique_slice(line, 14)
+}
+
+pub struct Aoc06;
 
-    aoc06_1(&lines);
-    aoc06_2(&lines);
+impl Solver for Aoc06 {
+    type Input = String;
+
+    fn parse(&self, input: &str) -> Result<String> {
+        Ok(input.trim().to_string())
+    }
+
+    fn part1(&self, line: &String) -> Result<Answer> {
+        Ok(aoc06_1(line)?.into())
+    }
+
+    fn part2(&self, line: &String) -> Result<Answer> {
+        Ok(aoc06_2(line)?.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn examples() {
+        let examples = [
+            ("mjqjpqmgbljsphdztnvjfqwrcgsmlb", 7, 19),
+            ("bvwbjplbgvbhsrlpgdmjqwftvncz", 5, 23),
+            ("nppdvjthqldpwncqszvftbrmjlhg", 6, 23),
+            ("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg", 10, 29),
+            ("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw", 11, 26),
+        ];
+        for (buffer, packet, message) in examples {
+            assert_eq!(aoc06_1(buffer).unwrap(), packet);
+            assert_eq!(aoc06_2(buffer).unwrap(), message);
+        }
+    }
+
+    #[test]
+    fn no_marker() {
+        assert!(matches!(aoc06_1("abcabc"), Err(Error::NoSolution(_))));
+    }
+
+    #[test]
+    fn all_markers() {
+        let found: Vec<usize> = markers("abcabcc\nd".as_bytes(), 3)
+            .map(|m| m.unwrap())
+            .collect();
+        assert_eq!(found, [3, 4, 5, 6]);
+        let found: Vec<usize> = markers("aabba".as_bytes(), 1).map(|m| m.unwrap()).collect();
+        assert_eq!(found, [1, 2, 3, 4, 5]);
+        assert_eq!(
+            markers("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw".as_bytes(), 14).count(),
+            7
+        );
+    }
+
+    /// endless alphabet, generated on the fly
+    struct Alphabet(usize);
+
+    impl Read for Alphabet {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            for b in buf.iter_mut() {
+                *b = b'a' + (self.0 % 26) as u8;
+                self.0 += 1;
+            }
+            Ok(buf.len())
+        }
+    }
+
+    #[test]
+    fn long_stream() {
+        let len = 1 << 20;
+        assert_eq!(
+            markers(Alphabet(0).take(len), 26).count(),
+            len as usize - 25
+        );
+        assert_eq!(markers(Alphabet(0).take(len), 27).count(), 0);
+    }
 }
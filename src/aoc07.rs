@@ -1,125 +1,477 @@
-use parse_int::parse;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-use substring::Substring;
-
-#[derive(Debug, Clone)]
-struct Path {
-    size: usize,
-    path: String,
-    file: bool,
+use std::fmt::Write;
+
+use crate::error::{self, Error, Result};
+use crate::solver::{Answer, Solver};
+
+/// size of the whole disk
+pub const DISK_SIZE: usize = 70000000;
+/// free space the update needs
+pub const REQUIRED_SPACE: usize = 30000000;
+/// directories up to this size count towards part 1
+pub const SMALL_DIR: usize = 100000;
+
+/// index of a node in the [`FileSystem`] arena
+pub type NodeId = usize;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Kind {
+    Dir { children: Vec<NodeId> },
+    File,
 }
 
-fn get_parent<'a>(path: &'a str) -> &'a str {
-    return path.substring(0, path.rfind("/").unwrap()).into();
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Node {
+    pub name: String,
+    pub parent: Option<NodeId>,
+    /// size of the file, or the recursive size of the directory
+    pub size: usize,
+    pub kind: Kind,
 }
 
-fn get_dirsize(paths: &Vec<Path>, path: &str) -> usize {
-    let mut s: usize = 0;
-    for p in paths.iter() {
-        if p.file && p.path.starts_with(path) {
-            s += p.size
-        }
+impl Node {
+    pub fn is_dir(&self) -> bool {
+        matches!(self.kind, Kind::Dir { .. })
     }
-    return s;
 }
 
-fn aoc07_1(paths: &Vec<Path>) {
-    println!("solving AOC day 7 part 1");
+/// Arena-backed filesystem tree, the root directory is always node 0.
+///
+/// Nodes are only ever appended, so every node comes after its parent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileSystem {
+    nodes: Vec<Node>,
+}
 
-    let mut s = 0;
+impl Default for FileSystem {
+    fn default() -> Self {
+        FileSystem::new()
+    }
+}
 
-    for p in paths.iter() {
-        if p.file {
-            continue;
-        }
-        if p.size <= 100000 {
-            s += p.size;
+impl FileSystem {
+    pub const ROOT: NodeId = 0;
+
+    pub fn new() -> FileSystem {
+        FileSystem {
+            nodes: vec![Node {
+                name: "/".to_string(),
+                parent: None,
+                size: 0,
+                kind: Kind::Dir { children: vec![] },
+            }],
         }
     }
 
-    println!("sum small sizes: {}", s);
-}
+    pub fn node(&self, id: NodeId) -> &Node {
+        &self.nodes[id]
+    }
 
-fn aoc07_2(paths: &Vec<Path>) {
-    println!("solving AOC day 7 part 2");
+    pub fn children(&self, id: NodeId) -> &[NodeId] {
+        match &self.nodes[id].kind {
+            Kind::Dir { children } => children,
+            Kind::File => &[],
+        }
+    }
 
-    let total_space = 70000000;
-    let used_space = paths[0].size;
-    let free_space = total_space - used_space;
-    let need_to_free = 30000000 - free_space;
+    /// the entry `name` in the directory `dir`
+    pub fn child(&self, dir: NodeId, name: &str) -> Option<NodeId> {
+        self.children(dir)
+            .iter()
+            .copied()
+            .find(|&c| self.nodes[c].name == name)
+    }
 
-    let mut s = usize::MAX;
-    for p in paths.iter() {
-        if p.file {
-            continue;
-        }
-        if p.size > need_to_free && p.size < s {
-            s = p.size;
+    /// Adds a directory or, if `file_size` is given, a file to `dir`.
+    ///
+    /// The sizes of all directories above are updated on the way.
+    pub fn add(&mut self, dir: NodeId, name: &str, file_size: Option<usize>) -> NodeId {
+        let id = self.nodes.len();
+        let Kind::Dir { children } = &mut self.nodes[dir].kind else {
+            panic!("{} is not a directory", self.path(dir));
+        };
+        children.push(id);
+        self.nodes.push(Node {
+            name: name.to_string(),
+            parent: Some(dir),
+            size: file_size.unwrap_or(0),
+            kind: match file_size {
+                Some(_) => Kind::File,
+                None => Kind::Dir { children: vec![] },
+            },
+        });
+
+        let size = file_size.unwrap_or(0);
+        let mut ancestor = Some(dir);
+        while let Some(a) = ancestor {
+            self.nodes[a].size += size;
+            ancestor = self.nodes[a].parent;
         }
+        id
     }
-    println!("free that size: {}", s)
-}
 
-pub fn aoc07() {
-    let reader = BufReader::new(File::open("input-07").unwrap());
-    let mut paths: Vec<Path> = vec![Path {
-        size: 0,
-        path: "/".to_string(),
-        file: false,
-    }];
-    let mut current_path: String = format!("/");
-
-    for (_index, line) in reader.lines().enumerate() {
-        let line = line.unwrap();
-        if line == "$ cd /" {
-            current_path = format!("/");
-            continue;
+    /// absolute path of the node, `/` for the root
+    pub fn path(&self, id: NodeId) -> String {
+        let mut names = vec![];
+        let mut current = id;
+        while let Some(parent) = self.nodes[current].parent {
+            names.push(self.nodes[current].name.as_str());
+            current = parent;
         }
-        if line == "$ ls" {
-            continue;
+        names.reverse();
+        format!("/{}", names.join("/"))
+    }
+
+    pub fn used(&self) -> usize {
+        self.nodes[FileSystem::ROOT].size
+    }
+
+    pub fn dirs(&self) -> impl Iterator<Item = NodeId> + '_ {
+        (0..self.nodes.len()).filter(|&id| self.nodes[id].is_dir())
+    }
+
+    /// sum of the sizes of all directories of at most `limit`
+    pub fn small_dirs_total(&self, limit: usize) -> usize {
+        self.dirs()
+            .map(|d| self.nodes[d].size)
+            .filter(|&size| size <= limit)
+            .sum()
+    }
+
+    /// smallest directory to delete so that `required` bytes are free on a `disk` sized disk
+    pub fn smallest_dir_freeing(&self, disk: usize, required: usize) -> Option<NodeId> {
+        let missing = required.saturating_sub(disk.saturating_sub(self.used()));
+        self.dirs()
+            .filter(|&d| self.nodes[d].size >= missing)
+            .min_by_key(|&d| self.nodes[d].size)
+    }
+
+    /// all directories as `size<TAB>path`, children before their parents, just like `du`
+    pub fn du(&self) -> String {
+        let mut out = String::new();
+        self.du_into(FileSystem::ROOT, &mut out);
+        out
+    }
+
+    fn du_into(&self, id: NodeId, out: &mut String) {
+        for &child in self.children(id) {
+            if self.nodes[child].is_dir() {
+                self.du_into(child, out);
+            }
         }
+        writeln!(out, "{}\t{}", self.nodes[id].size, self.path(id)).unwrap();
+    }
+
+    /// the whole tree with sizes, drawn like `tree`
+    pub fn tree(&self) -> String {
+        let mut out = String::new();
+        writeln!(out, "/ ({})", self.used()).unwrap();
+        self.tree_into(FileSystem::ROOT, "", &mut out);
+        out
+    }
 
-        if line.starts_with("$ cd ") {
-            let d = line.substring(5, line.len());
-            if d == ".." {
-                current_path = get_parent(&current_path).into();
+    fn tree_into(&self, id: NodeId, prefix: &str, out: &mut String) {
+        let children = self.children(id);
+        for (i, &child) in children.iter().enumerate() {
+            let last = i + 1 == children.len();
+            let node = &self.nodes[child];
+            let name = if node.is_dir() {
+                format!("{}/", node.name)
             } else {
-                current_path = current_path + "/" + d;
+                node.name.clone()
+            };
+            writeln!(
+                out,
+                "{}{} {} ({})",
+                prefix,
+                if last { "└──" } else { "├──" },
+                name,
+                node.size
+            )
+            .unwrap();
+            if node.is_dir() {
+                let prefix = format!("{}{}", prefix, if last { "    " } else { "│   " });
+                self.tree_into(child, &prefix, out);
             }
-            continue;
         }
-        if line.starts_with("dir ") {
-            paths.push(Path {
-                path: format!(
-                    "{}/{}",
-                    current_path,
-                    line.substring(4, line.len()).to_string()
-                ),
-                size: 0,
-                file: false,
+    }
+}
+
+/// target of a `cd`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Target {
+    Root,
+    Parent,
+    Dir(String),
+}
+
+/// one line of `ls` output
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Entry {
+    Dir(String),
+    File { name: String, size: usize },
+}
+
+impl Entry {
+    pub fn name(&self) -> &str {
+        match self {
+            Entry::Dir(name) | Entry::File { name, .. } => name,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    Cd(Target),
+    Ls(Vec<Entry>),
+}
+
+/// a command together with the 1-based line it was typed on
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step {
+    pub line: usize,
+    pub command: Command,
+}
+
+/// parses the terminal transcript into commands, the output of `ls` becomes its entries
+pub fn parse_transcript(input: &str) -> Result<Vec<Step>> {
+    let mut steps: Vec<Step> = vec![];
+
+    for (index, line) in input.lines().enumerate() {
+        if let Some(command) = line.strip_prefix("$ ") {
+            let command = match command.split_once(' ') {
+                None if command == "ls" => Command::Ls(vec![]),
+                Some(("cd", "/")) => Command::Cd(Target::Root),
+                Some(("cd", "..")) => Command::Cd(Target::Parent),
+                Some(("cd", dir)) if !dir.is_empty() && !dir.contains('/') => {
+                    Command::Cd(Target::Dir(dir.to_string()))
+                }
+                _ => {
+                    return Err(Error::parse(
+                        index + 1,
+                        3,
+                        format!("unknown command {:?}", command),
+                    ))
+                }
+            };
+            steps.push(Step {
+                line: index + 1,
+                command,
             });
             continue;
         }
-        let parts = line.split(" ").collect::<Vec<&str>>();
-        paths.push(Path {
-            path: format!("{}/{}", current_path, parts[1]),
-            size: parse::<usize>(parts[0]).unwrap(),
-            file: true,
-        })
+
+        let Some(Step {
+            command: Command::Ls(entries),
+            ..
+        }) = steps.last_mut()
+        else {
+            return Err(Error::line(
+                index,
+                format!("output {:?} without an ls", line),
+            ));
+        };
+        let (size, name) = line
+            .split_once(' ')
+            .filter(|(_, name)| !name.is_empty() && !name.contains('/'))
+            .ok_or_else(|| Error::line(index, format!("unexpected output {:?}", line)))?;
+        let entry = match size {
+            "dir" => Entry::Dir(name.to_string()),
+            size => Entry::File {
+                name: name.to_string(),
+                size: error::number::<usize>(size, index, 0)?,
+            },
+        };
+        if entries.iter().any(|e| e.name() == entry.name()) {
+            return Err(Error::line(
+                index,
+                format!("{:?} is listed twice", entry.name()),
+            ));
+        }
+        entries.push(entry);
     }
 
-    for i in 0..paths.len() {
-        let p = paths.get(i).unwrap();
-        if p.file {
-            continue;
+    Ok(steps)
+}
+
+impl FileSystem {
+    /// Replays the session, rejecting anything a real shell would not have printed.
+    ///
+    /// Every directory may be listed once and `cd` only enters directories that
+    /// showed up in a listing of the current directory before.
+    pub fn from_transcript(steps: &[Step]) -> Result<FileSystem> {
+        let mut fs = FileSystem::new();
+        let mut listed = vec![false];
+        let mut cwd = FileSystem::ROOT;
+
+        for step in steps {
+            let error = |message: String| Error::parse(step.line, 1, message);
+            match &step.command {
+                Command::Cd(Target::Root) => cwd = FileSystem::ROOT,
+                Command::Cd(Target::Parent) => {
+                    cwd = fs
+                        .node(cwd)
+                        .parent
+                        .ok_or_else(|| error("cd .. past the root".to_string()))?;
+                }
+                Command::Cd(Target::Dir(dir)) => {
+                    cwd = fs
+                        .child(cwd, dir)
+                        .filter(|&d| fs.node(d).is_dir())
+                        .ok_or_else(|| {
+                            error(format!(
+                                "cd into {:?}, which is not listed in {}",
+                                dir,
+                                fs.path(cwd)
+                            ))
+                        })?;
+                }
+                Command::Ls(entries) => {
+                    if listed[cwd] {
+                        return Err(error(format!("{} is listed twice", fs.path(cwd))));
+                    }
+                    listed[cwd] = true;
+                    for entry in entries {
+                        match entry {
+                            Entry::Dir(name) => fs.add(cwd, name, None),
+                            Entry::File { name, size } => fs.add(cwd, name, Some(*size)),
+                        };
+                        listed.push(false);
+                    }
+                }
+            }
         }
-        let size = get_dirsize(&paths, &p.path);
 
-        let mut p = paths.get_mut(i).unwrap();
-        p.size = size;
+        Ok(fs)
     }
+}
+
+pub struct Aoc07;
 
-    aoc07_1(&paths);
-    aoc07_2(&paths);
+impl Solver for Aoc07 {
+    type Input = FileSystem;
+
+    fn parse(&self, input: &str) -> Result<FileSystem> {
+        FileSystem::from_transcript(&parse_transcript(input)?)
+    }
+
+    fn part1(&self, fs: &FileSystem) -> Result<Answer> {
+        Ok(fs.small_dirs_total(SMALL_DIR).into())
+    }
+
+    fn part2(&self, fs: &FileSystem) -> Result<Answer> {
+        let dir = fs
+            .smallest_dir_freeing(DISK_SIZE, REQUIRED_SPACE)
+            .ok_or_else(|| Error::no_solution("no directory frees enough space"))?;
+        Ok(fs.node(dir).size.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+$ cd /
+$ ls
+dir a
+14848514 b.txt
+8504156 c.dat
+dir d
+$ cd a
+$ ls
+dir e
+29116 f
+2557 g
+62596 h.lst
+$ cd e
+$ ls
+584 i
+$ cd ..
+$ cd ..
+$ cd d
+$ ls
+4060174 j
+8033020 d.log
+5626152 d.ext
+7214296 k
+";
+
+    #[test]
+    fn example() {
+        let fs = Aoc07.parse(EXAMPLE).unwrap();
+        assert_eq!(Aoc07.part1(&fs).unwrap(), Answer::from(95437));
+        assert_eq!(Aoc07.part2(&fs).unwrap(), Answer::from(24933642));
+    }
+
+    #[test]
+    fn sizes_are_per_directory() {
+        let fs = Aoc07
+            .parse("$ cd /\n$ ls\ndir a\ndir ab\n$ cd a\n$ ls\n10 x\n$ cd ..\n$ cd ab\n$ ls\n5 y\n")
+            .unwrap();
+        let a = fs.child(FileSystem::ROOT, "a").unwrap();
+        assert_eq!(fs.node(a).size, 10);
+        assert_eq!(fs.path(a), "/a");
+        assert_eq!(fs.used(), 15);
+        assert_eq!(fs.smallest_dir_freeing(100, 95), Some(a));
+        assert_eq!(fs.smallest_dir_freeing(100, 101), None);
+    }
+
+    #[test]
+    fn transcript() {
+        let steps = parse_transcript("$ cd /\n$ ls\ndir a\n14848514 b.txt\n").unwrap();
+        assert_eq!(
+            steps,
+            [
+                Step {
+                    line: 1,
+                    command: Command::Cd(Target::Root)
+                },
+                Step {
+                    line: 2,
+                    command: Command::Ls(vec![
+                        Entry::Dir("a".to_string()),
+                        Entry::File {
+                            name: "b.txt".to_string(),
+                            size: 14848514
+                        }
+                    ])
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_sessions() {
+        for (input, line) in [
+            ("10 a\n", 1),
+            ("$ ls\n10 a\n$ cd /\n$ ls\n", 4),
+            ("$ ls\n10 a\n10 a\n", 3),
+            ("$ ls\ndir a\n$ cd b\n", 3),
+            ("$ ls\n10 a\n$ cd a\n", 3),
+            ("$ cd /\n$ cd ..\n", 2),
+            ("$ ls\nten a\n", 2),
+            ("$ rm -rf a\n", 1),
+        ] {
+            match Aoc07.parse(input) {
+                Err(Error::Parse { line: l, .. }) => assert_eq!(l, line, "{:?}", input),
+                other => panic!("expected a parse error for {:?}, got {:?}", input, other),
+            }
+        }
+    }
+
+    #[test]
+    fn renderings() {
+        let fs = Aoc07.parse(EXAMPLE).unwrap();
+        assert_eq!(fs.du(), "584\t/a/e\n94853\t/a\n24933642\t/d\n48381165\t/\n");
+        assert_eq!(
+            fs.tree().lines().take(5).collect::<Vec<&str>>(),
+            [
+                "/ (48381165)",
+                "├── a/ (94853)",
+                "│   ├── e/ (584)",
+                "│   │   └── i (584)",
+                "│   ├── f (29116)",
+            ]
+        );
+    }
 }
@@ -1,158 +1,289 @@
-use min_max::*;
 use std::fmt;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+
+use crate::error::Result;
+use crate::grid::Grid;
+use crate::solver::{Answer, Solver};
 
 const RADIX: u32 = 10;
 
 #[derive(Debug, Clone)]
-struct Map {
-    matrix: Vec<Vec<usize>>,
+pub struct Map {
+    trees: Grid<usize>,
     rows: usize,
     columns: usize,
 }
 
 impl fmt::Display for Map {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{:?}", self.matrix)
+        write!(f, "{}", self.trees)
     }
 }
 
-impl Map {
-    fn value(&self, row: usize, column: usize) -> usize {
-        self.matrix.get(row).unwrap().get(column).unwrap().clone()
+/// What a single tree sees.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct View {
+    /// true if the tree can be seen from outside the grid
+    pub visible: bool,
+    /// viewing distances up, right, down and left, the order of [`NEIGHBOURS4`](crate::grid::NEIGHBOURS4)
+    pub distances: [usize; 4],
+}
+
+impl View {
+    pub fn scenic_score(&self) -> usize {
+        self.distances.iter().product()
     }
+}
 
-    fn on_edge(&self, row: usize, column: usize) -> bool {
-        row == 0 || column == 0 || row == self.rows - 1 || column == self.columns - 1
+impl Map {
+    fn value(&self, row: usize, column: usize) -> usize {
+        self.trees[(column, row)]
     }
 
-    fn scenic_score_row<I>(&self, range: I, column: usize, value: usize) -> usize
-    where
-        I: Iterator<Item = usize>,
-    {
-        range.take_while(|r| self.value(*r, column) < value).count()
+    /// Looks at every tree from all four sides.
+    ///
+    /// Every line of trees is swept once per direction, keeping a stack of
+    /// the trees not yet blocked by a taller one. The tree on top of the
+    /// stack after popping all smaller ones is the one blocking the view,
+    /// so the whole survey takes O(rows * columns).
+    pub fn survey(&self) -> Grid<View> {
+        let mut views = Grid::new(self.columns, self.rows, View::default());
+        for c in 0..self.columns {
+            let column = || (0..self.rows).map(move |r| (r, c));
+            self.sweep(column(), 0, &mut views);
+            self.sweep(column().rev(), 2, &mut views);
+        }
+        for r in 0..self.rows {
+            let row = || (0..self.columns).map(move |c| (r, c));
+            self.sweep(row().rev(), 1, &mut views);
+            self.sweep(row(), 3, &mut views);
+        }
+        views
     }
 
-    fn scenic_score_col(
+    /// walks along the `line`, looking back in `direction` from every tree
+    fn sweep(
         &self,
-        range: impl Iterator<Item = usize>,
-        row: usize,
-        value: usize,
-    ) -> usize {
-        range.take_while(|c| self.value(row, *c) < value).count()
+        line: impl Iterator<Item = (usize, usize)>,
+        direction: usize,
+        views: &mut Grid<View>,
+    ) {
+        let mut stack: Vec<(usize, usize)> = vec![];
+        for (i, (row, column)) in line.enumerate() {
+            let height = self.value(row, column);
+            while stack.last().is_some_and(|&(_, h)| h < height) {
+                stack.pop();
+            }
+            let view = &mut views[(column, row)];
+            match stack.last() {
+                Some(&(blocker, _)) => view.distances[direction] = i - blocker,
+                None => {
+                    view.distances[direction] = i;
+                    view.visible = true;
+                }
+            }
+            stack.push((i, height));
+        }
     }
 
-    fn scenic_score(&self, row: usize, column: usize) -> usize {
-        let value = self.value(row, column);
-        let mut score: usize = 1;
+    /// true for every tree that can be seen from outside the grid
+    pub fn visibility(&self) -> Grid<bool> {
+        self.survey().map(|view| view.visible)
+    }
 
-        // rows before
-        score *= min!(
-            row,
-            self.scenic_score_row((0..row).rev(), column, value) + 1
-        );
+    pub fn scenic_scores(&self) -> Grid<usize> {
+        self.survey().map(View::scenic_score)
+    }
+}
 
-        // rows after
-        score *= min!(
-            self.rows - row - 1,
-            self.scenic_score_row(row + 1..self.rows, column, value) + 1
-        );
+/// the per tree values that can be drawn
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layer {
+    Height,
+    Visibility,
+    ScenicScore,
+}
 
-        // cols before
-        score *= min!(
-            column,
-            self.scenic_score_col((0..column).rev(), row, value) + 1
-        );
+/// highest value of a grey image
+const MAX_GREY: usize = 255;
 
-        // cols after
-        score *= min!(
-            self.columns - column - 1,
-            self.scenic_score_col(column + 1..self.columns, row, value) + 1
-        );
+impl Map {
+    /// the values of the `layer` and the largest one they can take
+    pub fn layer(&self, layer: Layer) -> (Grid<usize>, usize) {
+        match layer {
+            Layer::Height => (self.trees.clone(), RADIX as usize - 1),
+            Layer::Visibility => (self.visibility().map(|v| *v as usize), 1),
+            Layer::ScenicScore => {
+                let scores = self.scenic_scores();
+                let max = scores.iter().map(|(_, s)| *s).max().unwrap_or(0);
+                (scores, max)
+            }
+        }
+    }
 
-        score
+    /// the `layer` scaled to grey values from 0 to [`MAX_GREY`]
+    fn grey(&self, layer: Layer) -> Grid<usize> {
+        let (values, max) = self.layer(layer);
+        values.map(|v| v * MAX_GREY / max.max(1))
     }
 
-    fn is_visible(&self, row: usize, column: usize) -> bool {
-        let value = self.value(row, column);
+    /// position of the tree with the highest scenic score, the first one on ties
+    pub fn best_tree(&self) -> Option<(usize, usize)> {
+        let scores = self.scenic_scores();
+        let best = scores.iter().map(|(_, s)| *s).max()?;
+        scores.find(|s| *s == best)
+    }
 
-        if (0..row).all(|r| self.value(r, column) < value) {
-            return true;
-        }
-        if (row + 1..self.rows).all(|r| self.value(r, column) < value) {
-            return true;
+    /// the `layer` as plain PGM grey map
+    pub fn to_pgm(&self, layer: Layer) -> String {
+        let grey = self.grey(layer);
+        let mut pgm = format!("P2\n{} {}\n{}\n", self.columns, self.rows, MAX_GREY);
+        for row in grey.rows() {
+            let row: Vec<String> = row.iter().map(|g| g.to_string()).collect();
+            pgm += &row.join(" ");
+            pgm.push('\n');
         }
-        if (0..column).all(|c| self.value(row, c) < value) {
-            return true;
-        }
-        if (column + 1..self.columns).all(|c| self.value(row, c) < value) {
-            return true;
-        }
-
-        false
+        pgm
     }
-}
 
-fn aoc08_1(map: &Map) {
-    println!("solving AOC day 8 part 1");
-    let mut score: usize = 0;
+    /// the `layer` as plain PPM colour image, with the best tree in red
+    pub fn to_ppm(&self, layer: Layer) -> String {
+        let grey = self.grey(layer);
+        let best = self.best_tree();
+        let mut ppm = format!("P3\n{} {}\n{}\n", self.columns, self.rows, MAX_GREY);
+        for y in 0..self.rows {
+            let row: Vec<String> = (0..self.columns)
+                .map(|x| {
+                    if Some((x, y)) == best {
+                        format!("{} 0 0", MAX_GREY)
+                    } else {
+                        format!("{0} {0} {0}", grey[(x, y)])
+                    }
+                })
+                .collect();
+            ppm += &row.join("  ");
+            ppm.push('\n');
+        }
+        ppm
+    }
 
-    for row in 0..map.rows {
-        for column in 0..map.columns {
-            if map.on_edge(row, column) {
-                score += 1;
-            } else if map.is_visible(row, column) {
-                score += 1;
+    /// The heights coloured by the `layer` for the terminal, the best tree on red.
+    ///
+    /// Uses the 24 grey shades of the 256 colour palette as background.
+    pub fn to_ansi(&self, layer: Layer) -> String {
+        let grey = self.grey(layer);
+        let best = self.best_tree();
+        let mut out = String::new();
+        for y in 0..self.rows {
+            for x in 0..self.columns {
+                let (foreground, background) = if Some((x, y)) == best {
+                    (97, "41".to_string())
+                } else {
+                    let shade = 232 + grey[(x, y)] * 23 / MAX_GREY;
+                    let foreground = if grey[(x, y)] > MAX_GREY / 2 { 30 } else { 97 };
+                    (foreground, format!("48;5;{}", shade))
+                };
+                out += &format!("\x1b[{};{}m{}", foreground, background, self.trees[(x, y)]);
             }
+            out += "\x1b[0m\n";
         }
+        out
     }
-    println!("score: {:?}", score);
 }
 
-fn aoc08_2(map: &Map) {
-    println!("solving AOC day 8 part 2");
-    let mut score: usize = 0;
+fn aoc08_1(map: &Map) -> usize {
+    map.survey().iter().filter(|(_, view)| view.visible).count()
+}
+
+fn aoc08_2(map: &Map) -> usize {
+    map.survey()
+        .iter()
+        .map(|(_, view)| view.scenic_score())
+        .max()
+        .unwrap_or(0)
+}
 
-    for row in 0..map.rows {
-        for column in 0..map.columns {
-            if map.on_edge(row, column) {
-                continue;
-            }
-            let s = map.scenic_score(row, column);
-            if s > score {
-                score = s;
-            }
-        }
+pub struct Aoc08;
+
+impl Solver for Aoc08 {
+    type Input = Map;
+
+    fn parse(&self, input: &str) -> Result<Map> {
+        let trees = Grid::parse(input, |c| c.to_digit(RADIX).map(|height| height as usize))?;
+        Ok(Map {
+            rows: trees.height(),
+            columns: trees.width(),
+            trees,
+        })
+    }
+
+    fn part1(&self, map: &Map) -> Result<Answer> {
+        Ok(aoc08_1(map).into())
+    }
+
+    fn part2(&self, map: &Map) -> Result<Answer> {
+        Ok(aoc08_2(map).into())
     }
-    println!("score: {:?}", score);
 }
 
-pub fn aoc08() {
-    let reader = BufReader::new(File::open("input-08").unwrap());
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    let mut matrix: Vec<Vec<usize>> = vec![];
-    let mut rows: usize = 0;
-    let mut columns: usize = 0;
+    const EXAMPLE: &str = "\
+30373
+25512
+65332
+33549
+35390
+";
 
-    for (_index, line) in reader.lines().enumerate() {
-        let line = line.unwrap();
-        matrix.push(
-            line.chars()
-                .map(|c| c.to_digit(RADIX).unwrap().try_into().unwrap())
-                .collect(),
+    #[test]
+    fn example() {
+        let map = Aoc08.parse(EXAMPLE).unwrap();
+        assert_eq!(Aoc08.part1(&map).unwrap(), Answer::from(21));
+        assert_eq!(Aoc08.part2(&map).unwrap(), Answer::from(8));
+    }
+
+    #[test]
+    fn survey() {
+        let map = Aoc08.parse(EXAMPLE).unwrap();
+        let views = map.survey();
+        // the 5 in the middle of the second row
+        assert_eq!(
+            views[(2, 1)],
+            View {
+                visible: true,
+                distances: [1, 2, 2, 1]
+            }
+        );
+        // the 5 in the middle of the fourth row
+        assert_eq!(views[(2, 3)].distances, [2, 2, 1, 2]);
+        assert_eq!(map.scenic_scores()[(2, 3)], 8);
+        assert_eq!(
+            map.visibility().to_string(),
+            "TTTTT\nTTTFT\nTTFTT\nTFTFT\nTTTTT\n"
+                .replace('T', "true")
+                .replace('F', "false")
         );
-        rows += 1;
-        if columns == 0 {
-            columns = line.len();
-        }
     }
-    let map = Map {
-        matrix,
-        rows,
-        columns,
-    };
 
-    aoc08_1(&map);
-    aoc08_2(&map);
+    #[test]
+    fn images() {
+        let map = Aoc08.parse("12\n30\n").unwrap();
+        assert_eq!(map.best_tree(), Some((0, 0)));
+        assert_eq!(map.to_pgm(Layer::Height), "P2\n2 2\n255\n28 56\n85 0\n");
+        assert_eq!(
+            map.to_ppm(Layer::Visibility),
+            "P3\n2 2\n255\n255 0 0  255 255 255\n255 255 255  255 255 255\n"
+        );
+        assert_eq!(
+            map.to_ansi(Layer::Height).lines().nth(1).unwrap(),
+            "\x1b[97;48;5;239m3\x1b[97;48;5;232m0\x1b[0m"
+        );
+
+        let map = Aoc08.parse(EXAMPLE).unwrap();
+        assert_eq!(map.best_tree(), Some((2, 3)));
+        assert_eq!(map.layer(Layer::ScenicScore).1, 8);
+        assert!(map.to_ansi(Layer::ScenicScore).contains("\x1b[97;41m5"));
+    }
 }
@@ -1,171 +1,278 @@
-use parse_int::parse;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-use substring::Substring;
+use std::collections::HashSet;
 
-fn sign(n: isize) -> isize {
-    if n > 0 {
-        return 1;
+use crate::error::{self, Error, Result};
+use crate::solver::{Answer, Solver};
+
+/// directions the head can move in, the straight ones and the diagonals
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Up,
+    Down,
+    Right,
+    Left,
+    UpRight,
+    UpLeft,
+    DownRight,
+    DownLeft,
+}
+
+impl Direction {
+    const ALL: [Direction; 8] = [
+        Direction::Up,
+        Direction::Down,
+        Direction::Right,
+        Direction::Left,
+        Direction::UpRight,
+        Direction::UpLeft,
+        Direction::DownRight,
+        Direction::DownLeft,
+    ];
+
+    /// the name used in the input, a diagonal like `UR` names both parts
+    pub fn name(&self) -> &'static str {
+        match self {
+            Direction::Up => "U",
+            Direction::Down => "D",
+            Direction::Right => "R",
+            Direction::Left => "L",
+            Direction::UpRight => "UR",
+            Direction::UpLeft => "UL",
+            Direction::DownRight => "DR",
+            Direction::DownLeft => "DL",
+        }
     }
-    if n < 0 {
-        return -1;
+
+    /// the change of x and y for one step
+    fn delta(&self) -> (isize, isize) {
+        match self {
+            Direction::Up => (0, 1),
+            Direction::Down => (0, -1),
+            Direction::Right => (1, 0),
+            Direction::Left => (-1, 0),
+            Direction::UpRight => (1, 1),
+            Direction::UpLeft => (-1, 1),
+            Direction::DownRight => (1, -1),
+            Direction::DownLeft => (-1, -1),
+        }
     }
-    return 0;
 }
 
-#[derive(Debug, Clone)]
-struct Position {
-    x: isize,
-    y: isize,
+/// a direction and the number of steps to go
+pub type Motion = (Direction, usize);
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Position {
+    pub x: isize,
+    pub y: isize,
 }
 
 impl Position {
-    fn new(x: isize, y: isize) -> Position {
+    pub fn new(x: isize, y: isize) -> Position {
         Position { x, y }
     }
 
-    fn update(&mut self, char: &char) {
-        match char {
-            'U' => self.y += 1,
-            'D' => self.y -= 1,
-            'R' => self.x += 1,
-            'L' => self.x -= 1,
-            _ => panic!(),
-        }
+    /// one step in the `direction`, a diagonal takes both at once
+    pub fn update(&mut self, direction: Direction) {
+        let (dx, dy) = direction.delta();
+        self.x += dx;
+        self.y += dy;
     }
 
     fn need_move(&self, other: &Position) -> bool {
-        (self.x).abs_diff(other.x) > 1 || self.y.abs_diff(other.y) > 1
+        self.x.abs_diff(other.x) > 1 || self.y.abs_diff(other.y) > 1
     }
 
-    fn equals(&self, other: &Position) -> bool {
-        self.x == other.x && self.y == other.y
+    /// moves one step towards `head` if it is not touching anymore
+    fn follow(&mut self, head: &Position) {
+        if self.need_move(head) {
+            self.x += (head.x - self.x).signum();
+            self.y += (head.y - self.y).signum();
+        }
     }
 }
 
+/// A rope of knots, every knot follows the one before and the first is the head.
 #[derive(Debug, Clone)]
-struct Tail {
-    pos: Position,
-    history: Vec<Position>,
+pub struct Rope {
+    knots: Vec<Position>,
+    tracked: usize,
+    visited: HashSet<Position>,
 }
 
-impl Tail {
-    fn new() -> Tail {
-        Tail {
-            pos: Position::new(0, 0),
-            history: vec![],
+impl Rope {
+    /// a rope with `knots` knots, remembering the cells visited by the knot `tracked`
+    ///
+    /// # Panics
+    ///
+    /// if `tracked` is not less than `knots`
+    pub fn new(knots: usize, tracked: usize) -> Rope {
+        assert!(tracked < knots, "there is no knot {} to track", tracked);
+        Rope {
+            knots: vec![Position::default(); knots],
+            tracked,
+            visited: HashSet::from([Position::default()]),
         }
     }
 
-    fn follow(&mut self, head: &Position) {
-        if !self.pos.need_move(head) {
-            return;
-        }
+    /// a rope with `knots` knots that tracks its tail
+    ///
+    /// # Panics
+    ///
+    /// if `knots` is 0, a rope without knots has no tail
+    pub fn with_tail(knots: usize) -> Rope {
+        assert!(knots > 0, "a rope needs at least one knot");
+        Rope::new(knots, knots - 1)
+    }
 
-        self.history.push(self.pos.clone());
+    pub fn knots(&self) -> &[Position] {
+        &self.knots
+    }
 
-        // vertical movement
-        if self.pos.x == head.x {
-            self.pos.y += sign(head.y - self.pos.y);
-            return;
-        }
-        // horizontal movement
-        if self.pos.y == head.y {
-            self.pos.x += sign(head.x - self.pos.x);
-            return;
-        }
+    /// cells visited by the tracked knot, including the start
+    pub fn visited(&self) -> &HashSet<Position> {
+        &self.visited
+    }
 
-        // diagonal movement
-        if self.pos.x.abs_diff(head.x) == 1 {
-            self.pos.x = head.x;
-            self.pos.y += sign(head.y - self.pos.y);
-            return;
-        }
-        if self.pos.y.abs_diff(head.y) == 1 {
-            self.pos.y = head.y;
-            self.pos.x += sign(head.x - self.pos.x);
-            return;
+    /// moves the head a single step and lets the others follow
+    pub fn step(&mut self, direction: Direction) {
+        self.knots[0].update(direction);
+        for n in 1..self.knots.len() {
+            let head = self.knots[n - 1];
+            self.knots[n].follow(&head);
         }
-
-        self.pos.y += sign(head.y - self.pos.y);
-        self.pos.x += sign(head.x - self.pos.x);
+        self.visited.insert(self.knots[self.tracked]);
     }
 
-    fn n_steps(&self) -> usize {
-        let mut h = self.history.clone();
-        h.push(self.pos.clone());
-
-        h.sort_by(|a, b| {
-            if a.x == b.x {
-                return a.y.cmp(&b.y);
-            }
-            return a.x.cmp(&b.x);
-        });
-        let mut n_doubles = 0;
-        for i in 1..h.len() {
-            if h.get(i - 1).unwrap().equals(h.get(i).unwrap()) {
-                n_doubles += 1;
+    pub fn apply(&mut self, motions: &[Motion]) {
+        for (direction, steps) in motions.iter() {
+            for _ in 0..*steps {
+                self.step(*direction);
             }
         }
-        h.len() - n_doubles
     }
 }
 
-fn aoc09_1() {
-    println!("solving AOC day 9 part 1");
-    let reader = BufReader::new(File::open("input-09").unwrap());
-
-    let mut head = Position::new(0, 0);
-    let mut tail = Tail::new();
-
-    for (_index, line) in reader.lines().enumerate() {
-        let line = line.unwrap();
-        let direction = line.chars().nth(0).unwrap();
-        let mut steps = parse::<usize>(line.substring(2, line.len())).unwrap();
-        while steps > 0 {
-            head.update(&direction);
-            tail.follow(&head);
-            steps -= 1;
-        }
-    }
+fn aoc09_1(motions: &[Motion]) -> usize {
+    let mut rope = Rope::with_tail(2);
+    rope.apply(motions);
+    rope.visited().len()
+}
 
-    println!("n positions of tail: {}", tail.n_steps());
+fn aoc09_2(motions: &[Motion]) -> usize {
+    let mut rope = Rope::with_tail(10);
+    rope.apply(motions);
+    rope.visited().len()
 }
 
-fn aoc09_2() {
-    println!("solving AOC day 9 part 2");
-    let reader = BufReader::new(File::open("input-09").unwrap());
-
-    let mut head = Position::new(0, 0);
-    let mut tails: Vec<Tail> = vec![];
-    while tails.len() < 9 {
-        tails.push(Tail::new());
-    }
-
-    for (_index, line) in reader.lines().enumerate() {
-        let line = line.unwrap();
-        let direction = line.chars().nth(0).unwrap();
-        let mut steps = parse::<usize>(line.substring(2, line.len())).unwrap();
-
-        while steps > 0 {
-            head.update(&direction);
-            for n in 0..tails.len() {
-                if n == 0 {
-                    tails.get_mut(0).unwrap().follow(&head);
-                } else {
-                    let h = tails.get(n - 1).unwrap().pos.clone();
-                    tails.get_mut(n).unwrap().follow(&h);
-                }
-            }
-            steps -= 1;
-        }
+pub struct Aoc09;
+
+impl Solver for Aoc09 {
+    type Input = Vec<Motion>;
+
+    fn parse(&self, input: &str) -> Result<Vec<Motion>> {
+        input
+            .lines()
+            .enumerate()
+            .map(|(index, line)| {
+                let (direction, steps) = line
+                    .split_once(' ')
+                    .ok_or_else(|| Error::line(index, format!("invalid move {:?}", line)))?;
+                let offset = direction.len() + 1;
+                let direction = Direction::ALL
+                    .into_iter()
+                    .find(|d| d.name() == direction)
+                    .ok_or_else(|| {
+                        Error::line(index, format!("invalid direction {:?}", direction))
+                    })?;
+                let steps = error::number::<usize>(steps, index, offset)?;
+                Ok((direction, steps))
+            })
+            .collect()
+    }
+
+    fn part1(&self, motions: &Vec<Motion>) -> Result<Answer> {
+        Ok(aoc09_1(motions).into())
     }
-    for n in 0..tails.len() {
-        println!("tail {} {}", n, tails.get(n).unwrap().n_steps());
+
+    fn part2(&self, motions: &Vec<Motion>) -> Result<Answer> {
+        Ok(aoc09_2(motions).into())
     }
 }
 
-pub fn aoc09() {
-    aoc09_1();
-    aoc09_2();
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+R 4
+U 4
+L 3
+D 1
+R 4
+D 1
+L 5
+R 2
+";
+
+    const LARGER_EXAMPLE: &str = "\
+R 5
+U 8
+L 8
+D 3
+R 17
+D 10
+L 25
+U 20
+";
+
+    #[test]
+    fn example() {
+        let moves = Aoc09.parse(EXAMPLE).unwrap();
+        assert_eq!(Aoc09.part1(&moves).unwrap(), Answer::from(13));
+        assert_eq!(Aoc09.part2(&moves).unwrap(), Answer::from(1));
+    }
+
+    #[test]
+    fn larger_example() {
+        let moves = Aoc09.parse(LARGER_EXAMPLE).unwrap();
+        assert_eq!(Aoc09.part2(&moves).unwrap(), Answer::from(36));
+    }
+
+    #[test]
+    fn ropes() {
+        let moves = Aoc09.parse(EXAMPLE).unwrap();
+        // the head of a single knot rope visits every cell on its way
+        let mut head = Rope::with_tail(1);
+        head.apply(&moves);
+        assert_eq!(head.knots(), [Position::new(2, 2)]);
+
+        // the knot behind the head takes the same path in every rope
+        let mut rope = Rope::new(10, 1);
+        rope.apply(&moves);
+        assert_eq!(rope.visited().len(), 13);
+    }
+
+    #[test]
+    fn diagonal_moves() {
+        let moves = Aoc09.parse("UR 3\nDL 1\n").unwrap();
+        let mut rope = Rope::with_tail(3);
+        rope.apply(&moves);
+        assert_eq!(
+            rope.knots(),
+            [
+                Position::new(2, 2),
+                Position::new(2, 2),
+                Position::new(1, 1)
+            ]
+        );
+        assert_eq!(rope.visited().len(), 2);
+        assert_eq!(moves[0], (Direction::UpRight, 3));
+        assert!(Aoc09.parse("RU 1\n").is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "at least one knot")]
+    fn rope_without_knots() {
+        Rope::with_tail(0);
+    }
 }
@@ -1,7 +1,10 @@
-use parse_int::parse;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-use substring::Substring;
+use crate::error::{self, Error, Result};
+use crate::solver::{Answer, Solver};
+
+pub enum Instruction {
+    Noop,
+    Addx(isize),
+}
 
 fn check_cycle(cycle: isize, n: isize) -> isize {
     match cycle {
@@ -10,40 +13,350 @@ fn check_cycle(cycle: isize, n: isize) -> isize {
     }
 }
 
-fn print_crt(cycle: isize, n: isize) {
-    let c_mod = cycle - 1 % 40;
-    if n.abs_diff(c_mod % 40) < 2 {
-        print!("#");
-    } else {
-        print!(" ");
-    }
-    if cycle % 40 == 0 {
-        println!("");
+/// the glyphs of the 4x6 font drawn by the CRT, each row from left to right
+const FONT: [(char, &str); 17] = [
+    ('A', ".##.#..##..######..##..#"),
+    ('B', "###.#..####.#..##..####."),
+    ('C', ".##.#..##...#...#..#.##."),
+    ('E', "#####...###.#...#...####"),
+    ('F', "#####...###.#...#...#..."),
+    ('G', ".##.#..##...#.###..#.###"),
+    ('H', "#..##..######..##..##..#"),
+    ('I', ".###..#...#...#...#..###"),
+    ('J', "..##...#...#...##..#.##."),
+    ('K', "#..##.#.##..#.#.#.#.#..#"),
+    ('L', "#...#...#...#...#...####"),
+    ('O', ".##.#..##..##..##..#.##."),
+    ('P', "###.#..##..####.#...#..."),
+    ('R', "###.#..##..####.#.#.#..#"),
+    ('S', ".####...#....##....####."),
+    ('U', "#..##..##..##..##..#.##."),
+    ('Z', "####...#..#..#..#...####"),
+];
+
+fn draw_crt(screen: &mut [Vec<bool>], cycle: isize, n: isize) {
+    let c_mod = (cycle - 1) % 40;
+    let row = ((cycle - 1) / 40) as usize;
+    if n.abs_diff(c_mod) < 2 && row < screen.len() {
+        screen[row][c_mod as usize] = true;
     }
 }
 
-pub fn aoc10() {
-    println!("solving AOC day 10 part 2");
-    let reader = BufReader::new(File::open("input-10").unwrap());
+/// the screen as text, one line per row
+fn render_crt(screen: &[Vec<bool>]) -> String {
+    screen
+        .iter()
+        .map(|row| row.iter().map(|lit| if *lit { '#' } else { '.' }).collect())
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// reads the letters from a screen of 6 rows, each letter 4 pixels wide plus one pixel spacing
+fn read_crt(screen: &[Vec<bool>]) -> Result<String> {
+    (0..screen[0].len() / 5)
+        .map(|letter| {
+            let glyph: String = screen
+                .iter()
+                .flat_map(|row| row[letter * 5..letter * 5 + 4].iter())
+                .map(|lit| if *lit { '#' } else { '.' })
+                .collect();
+            FONT.iter()
+                .find(|(_, g)| *g == glyph)
+                .map(|(c, _)| *c)
+                .ok_or_else(|| {
+                    Error::no_solution(format!(
+                        "cannot read letter {} of the screen\n{}",
+                        letter + 1,
+                        render_crt(screen)
+                    ))
+                })
+        })
+        .collect()
+}
 
+/// runs the program and calls `tick` with the cycle number and the register value during every cycle
+fn run(program: &[Instruction], mut tick: impl FnMut(isize, isize)) {
     let mut cycle: isize = 0;
     let mut register: isize = 1;
-    let mut sum: isize = 0;
 
-    for (_index, line) in reader.lines().enumerate() {
-        let line = line.unwrap();
+    for instruction in program.iter() {
         cycle += 1;
-        sum += cycle * check_cycle(cycle, register);
-        print_crt(cycle, register);
-        if line == "noop" {
-            continue;
+        tick(cycle, register);
+        if let Instruction::Addx(n) = instruction {
+            cycle += 1;
+            tick(cycle, register);
+            register += n;
         }
-        cycle += 1;
+    }
+}
+
+fn aoc10_1(program: &[Instruction]) -> isize {
+    let mut sum: isize = 0;
+    run(program, |cycle, register| {
         sum += cycle * check_cycle(cycle, register);
-        print_crt(cycle, register);
-        let n = parse::<isize>(line.substring(5, line.len())).unwrap();
-        register += n;
+    });
+    sum
+}
+
+fn aoc10_2(program: &[Instruction]) -> Result<String> {
+    let mut screen = vec![vec![false; 40]; 6];
+    run(program, |cycle, register| {
+        draw_crt(&mut screen, cycle, register)
+    });
+    read_crt(&screen)
+}
+
+pub struct Aoc10;
+
+impl Solver for Aoc10 {
+    type Input = Vec<Instruction>;
+
+    fn parse(&self, input: &str) -> Result<Vec<Instruction>> {
+        input
+            .lines()
+            .enumerate()
+            .map(|(index, line)| {
+                if line == "noop" {
+                    Ok(Instruction::Noop)
+                } else if let Some(n) = line.strip_prefix("addx ") {
+                    Ok(Instruction::Addx(error::number::<isize>(n, index, 5)?))
+                } else {
+                    Err(Error::line(
+                        index,
+                        format!("unknown instruction {:?}", line),
+                    ))
+                }
+            })
+            .collect()
+    }
+
+    fn part1(&self, program: &Vec<Instruction>) -> Result<Answer> {
+        Ok(aoc10_1(program).into())
+    }
+
+    fn part2(&self, program: &Vec<Instruction>) -> Result<Answer> {
+        Ok(aoc10_2(program)?.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// the larger example program of the puzzle description
+    const EXAMPLE: &str = "\
+addx 15
+addx -11
+addx 6
+addx -3
+addx 5
+addx -1
+addx -8
+addx 13
+addx 4
+noop
+addx -1
+addx 5
+addx -1
+addx 5
+addx -1
+addx 5
+addx -1
+addx 5
+addx -1
+addx -35
+addx 1
+addx 24
+addx -19
+addx 1
+addx 16
+addx -11
+noop
+noop
+addx 21
+addx -15
+noop
+noop
+addx -3
+addx 9
+addx 1
+addx -3
+addx 8
+addx 1
+addx 5
+noop
+noop
+noop
+noop
+noop
+addx -36
+noop
+addx 1
+addx 7
+noop
+noop
+noop
+addx 2
+addx 6
+noop
+noop
+noop
+noop
+noop
+addx 1
+noop
+noop
+addx 7
+addx 1
+noop
+addx -13
+addx 13
+addx 7
+noop
+addx 1
+addx -33
+noop
+noop
+noop
+addx 2
+noop
+noop
+noop
+addx 8
+noop
+addx -1
+addx 2
+addx 1
+noop
+addx 17
+addx -9
+addx 1
+addx 1
+addx -3
+addx 11
+noop
+noop
+addx 1
+noop
+addx 1
+noop
+noop
+addx -13
+addx -19
+addx 1
+addx 3
+addx 26
+addx -30
+addx 12
+addx -1
+addx 3
+addx 1
+noop
+noop
+noop
+addx -9
+addx 18
+addx 1
+addx 2
+noop
+noop
+addx 9
+noop
+noop
+noop
+addx -1
+addx 2
+addx -37
+addx 1
+addx 3
+noop
+addx 15
+addx -21
+addx 22
+addx -6
+addx 1
+noop
+addx 2
+addx 1
+noop
+addx -10
+noop
+noop
+addx 20
+addx 1
+addx 2
+addx 2
+addx -6
+addx -11
+noop
+noop
+noop
+";
+
+    #[test]
+    fn example() {
+        let program = Aoc10.parse(EXAMPLE).unwrap();
+        assert_eq!(program.len(), 146);
+        assert_eq!(Aoc10.part1(&program).unwrap(), Answer::from(13140));
+
+        let mut screen = vec![vec![false; 40]; 6];
+        run(&program, |cycle, register| {
+            draw_crt(&mut screen, cycle, register)
+        });
+        let rendered = "\
+##..##..##..##..##..##..##..##..##..##..
+###...###...###...###...###...###...###.
+####....####....####....####....####....
+#####.....#####.....#####.....#####.....
+######......######......######......####
+#######.......#######.......#######.....";
+        assert_eq!(render_crt(&screen), rendered);
+
+        // the example draws a pattern, not letters
+        match Aoc10.part2(&program) {
+            Err(Error::NoSolution(message)) => assert!(message.ends_with(rendered)),
+            other => panic!("expected no solution, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn register() {
+        let program = Aoc10.parse("noop\naddx 3\naddx -5\n").unwrap();
+        let mut registers = vec![];
+        run(&program, |cycle, register| {
+            registers.push((cycle, register))
+        });
+        assert_eq!(registers, vec![(1, 1), (2, 1), (3, 1), (4, 4), (5, 4)]);
+    }
+
+    #[test]
+    fn signal_strength() {
+        // adds 1 in every second cycle, so X is 10 during cycle 20
+        let program = Aoc10.parse(&"addx 1\n".repeat(120)).unwrap();
+        assert_eq!(
+            Aoc10.part1(&program).unwrap(),
+            Answer::from(20 * 10 + 60 * 30 + 100 * 50 + 140 * 70 + 180 * 90 + 220 * 110)
+        );
+    }
+
+    #[test]
+    fn read_letters() {
+        let letters = "RUAKHBEK";
+        let screen: Vec<Vec<bool>> = (0..6)
+            .map(|row| {
+                letters
+                    .chars()
+                    .flat_map(|letter| {
+                        let (_, glyph) = FONT.iter().find(|(c, _)| *c == letter).unwrap();
+                        glyph[row * 4..row * 4 + 4]
+                            .chars()
+                            .map(|pixel| pixel == '#')
+                            .chain([false])
+                            .collect::<Vec<bool>>()
+                    })
+                    .collect()
+            })
+            .collect();
+        assert_eq!(read_crt(&screen).unwrap(), letters);
     }
-    println!("solving AOC day 10 part 1");
-    println!("score: {}", sum);
 }
@@ -1,10 +1,9 @@
-use parse_int::parse;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::collections::VecDeque;
 use std::vec;
-use substring::Substring;
 
-use queues::*;
+use crate::error::{self, Error, Result};
+use crate::input::{self, Record};
+use crate::solver::{Answer, Solver};
 
 struct Action {
     item: isize,
@@ -13,62 +12,128 @@ struct Action {
 
 #[derive(Debug)]
 struct Monkey {
-    id: usize,
-    items: Queue<isize>,
+    items: VecDeque<isize>,
     operation: char,
-    operation_target: String,
+    operation_target: Option<isize>,
     test: isize,
     target_true: usize,
     target_false: usize,
     items_seen: usize,
 }
 
+/// the description of a monkey as found in the puzzle input
+#[derive(Debug, Clone)]
+pub struct MonkeyNotes {
+    starting_items: Vec<isize>,
+    operation: char,
+    /// the second operand, `None` means the old value
+    operation_target: Option<isize>,
+    test: isize,
+    true_target: usize,
+    false_target: usize,
+}
+
+impl MonkeyNotes {
+    /// reads the notes about one monkey, a record of the input
+    fn new(record: &Record) -> Result<MonkeyNotes> {
+        let mut starting_items: Vec<isize> = vec![];
+        let mut operation: Option<(char, Option<isize>)> = None;
+        let mut test: Option<isize> = None;
+        let mut true_target: Option<usize> = None;
+        let mut false_target: Option<usize> = None;
+
+        for (index, line) in record.lines() {
+            if line.starts_with("Monkey") {
+                continue;
+            }
+            if let Some(items) = line.strip_prefix("  Starting items: ") {
+                starting_items = vec![];
+                let mut offset = 18;
+                for item in items.split(", ") {
+                    starting_items.push(error::number::<isize>(item, index, offset)?);
+                    offset += item.len() + 2;
+                }
+                continue;
+            }
+            if let Some(op) = line.strip_prefix("  Operation: new = old ") {
+                let (operator, target) = op
+                    .split_once(' ')
+                    .ok_or_else(|| Error::line(index, format!("invalid operation {:?}", op)))?;
+                let operator = match operator {
+                    "*" | "+" | "-" | "/" => operator.chars().next().unwrap(),
+                    _ => {
+                        return Err(Error::parse(
+                            index + 1,
+                            24,
+                            format!("do not understand operation {:?}", operator),
+                        ))
+                    }
+                };
+                let target = if target == "old" {
+                    None
+                } else {
+                    Some(error::number::<isize>(target, index, 25)?)
+                };
+                if operator == '/' && target == Some(0) {
+                    return Err(Error::parse(index + 1, 26, "cannot divide by 0"));
+                }
+                operation = Some((operator, target));
+                continue;
+            }
+            if let Some(n) = line.strip_prefix("  Test: divisible by ") {
+                let n = error::number::<isize>(n, index, 21)?;
+                if n == 0 {
+                    return Err(Error::parse(index + 1, 22, "cannot divide by 0"));
+                }
+                test = Some(n);
+                continue;
+            }
+            if let Some(n) = line.strip_prefix("    If true: throw to monkey ") {
+                true_target = Some(error::number::<usize>(n, index, 29)?);
+                continue;
+            }
+            if let Some(n) = line.strip_prefix("    If false: throw to monkey ") {
+                false_target = Some(error::number::<usize>(n, index, 30)?);
+                continue;
+            }
+            return Err(Error::line(index, format!("unexpected line {:?}", line)));
+        }
+
+        let incomplete = || Error::line(record.index, "incomplete notes of a monkey");
+        let (operation, operation_target) = operation.ok_or_else(incomplete)?;
+        Ok(MonkeyNotes {
+            starting_items,
+            operation,
+            operation_target,
+            test: test.ok_or_else(incomplete)?,
+            true_target: true_target.ok_or_else(incomplete)?,
+            false_target: false_target.ok_or_else(incomplete)?,
+        })
+    }
+}
+
 impl Monkey {
-    fn new(
-        id: usize,
-        items: String,
-        operation: String,
-        test: isize,
-        true_target: usize,
-        false_target: usize,
-    ) -> Monkey {
-        let operation_parts = operation.split(" ").collect::<Vec<&str>>();
-
-        let mut monkey = Monkey {
-            id,
-            items: queue![],
-            operation: operation_parts.get(1).unwrap().chars().nth(0).unwrap(),
-            operation_target: operation_parts.get(2).unwrap().to_string(),
-            test: test.clone(),
-            target_true: true_target.clone(),
-            target_false: false_target.clone(),
+    fn new(notes: &MonkeyNotes) -> Monkey {
+        Monkey {
+            items: notes.starting_items.iter().copied().collect(),
+            operation: notes.operation,
+            operation_target: notes.operation_target,
+            test: notes.test,
+            target_true: notes.true_target,
+            target_false: notes.false_target,
             items_seen: 0,
-        };
-        let item_parts = items.split(", ");
-        for item in item_parts {
-            monkey
-                .items
-                .add(parse::<isize>(item).unwrap())
-                .map_err(|err| println!("{:?}", err))
-                .ok();
         }
-
-        return monkey;
     }
 
     fn operate(&self, item: isize) -> isize {
-        let other = if self.operation_target == "old" {
-            item
-        } else {
-            parse::<isize>(&self.operation_target).unwrap()
-        };
+        let other = self.operation_target.unwrap_or(item);
 
         match self.operation {
             '*' => item * other,
             '+' => item + other,
             '-' => item - other,
             '/' => item / other,
-            _ => panic!("do not understand operation {} ", self.operation),
+            _ => unreachable!("operations are validated while parsing"),
         }
     }
 
@@ -82,15 +147,9 @@ impl Monkey {
     }
 
     fn turn(&mut self, part: usize, lcm: isize) -> Vec<Action> {
-        self.items_seen += self.items.size();
+        self.items_seen += self.items.len();
         let mut actions: Vec<Action> = vec![];
-        let mut n_items = self.items.size();
-        let mut todos: Vec<isize> = vec![];
-        while n_items > 0 {
-            todos.push(self.items.remove().unwrap());
-            n_items -= 1;
-        }
-        for item in todos {
+        for item in std::mem::take(&mut self.items) {
             let operated = if part == 1 {
                 self.operate(item) / 3
             } else {
@@ -102,171 +161,149 @@ impl Monkey {
             });
         }
 
-        return actions;
+        actions
     }
 }
 
-fn aoc11_1() {
-    println!("solving AOC day 11 part 1");
-    let reader = BufReader::new(File::open("input-11").unwrap());
-
-    let mut monkeys: Vec<Monkey> = vec![];
-    let mut monkey_id: Option<usize> = None;
-    let mut starting_items: String = String::from("");
-    let mut operation: String = String::from("");
-    let mut test: Option<isize> = None;
-    let mut true_target: Option<usize> = None;
-    let mut false_target: Option<usize> = None;
-
-    for (_index, line) in reader.lines().enumerate() {
-        let line = line.unwrap();
-        if line.starts_with("Monkey") {
-            monkey_id = Some(parse::<usize>(line.substring(7, line.len() - 1)).unwrap());
-            continue;
-        }
-        if line.starts_with("  Starting items: ") {
-            starting_items = line.substring(18, line.len()).to_string();
-            continue;
-        }
-        if line.starts_with("  Operation: new = ") {
-            operation = line.substring(19, line.len()).to_string();
-            continue;
-        }
-        if line.starts_with("  Test: divisible by ") {
-            test = Some(parse::<isize>(line.substring(21, line.len())).unwrap());
-            continue;
-        }
-        if line.starts_with("    If true: throw to monkey ") {
-            true_target = Some(parse::<usize>(line.substring(29, line.len())).unwrap());
-            continue;
-        }
-        if line.starts_with("    If false: throw to monkey ") {
-            false_target = Some(parse::<usize>(line.substring(30, line.len())).unwrap());
-            continue;
-        }
-        if line == "" {
-            monkeys.push(Monkey::new(
-                monkey_id.unwrap(),
-                starting_items.to_string(),
-                operation.to_string(),
-                test.unwrap(),
-                true_target.unwrap(),
-                false_target.unwrap(),
-            ));
-            continue;
-        }
+fn play(monkeys: &mut [Monkey], rounds: usize, part: usize, lcm: isize) -> Result<usize> {
+    if monkeys.len() < 2 {
+        return Err(Error::no_solution(format!(
+            "monkey business needs at least two monkeys, there are {}",
+            monkeys.len()
+        )));
     }
 
-    for _n in 0..20 {
+    for _n in 0..rounds {
         for m in 0..monkeys.len() {
             let current_monkey = monkeys.get_mut(m).unwrap();
-            let actions = current_monkey.turn(1, 0);
+            let actions = current_monkey.turn(part, lcm);
             for action in actions {
-                monkeys
-                    .get_mut(action.target)
-                    .unwrap()
-                    .items
-                    .add(action.item)
-                    .map_err(|err| println!("{:?}", err))
-                    .ok();
+                monkeys[action.target].items.push_back(action.item);
             }
         }
     }
 
-    monkeys.sort_by(|a, b| b.items_seen.cmp(&a.items_seen));
+    monkeys.sort_by_key(|m| std::cmp::Reverse(m.items_seen));
 
-    for monkey in monkeys.iter() {
-        println!("{} {}", monkey.id, monkey.items_seen);
-    }
+    Ok(monkeys[0].items_seen * monkeys[1].items_seen)
+}
 
-    let max = monkeys.get(0).unwrap().items_seen;
-    let max2 = monkeys.get(1).unwrap().items_seen;
+fn aoc11_1(notes: &[MonkeyNotes]) -> Result<usize> {
+    let mut monkeys: Vec<Monkey> = notes.iter().map(Monkey::new).collect();
+    play(&mut monkeys, 20, 1, 0)
+}
 
-    println!("{} * {} = {}", max, max2, max * max2);
+fn aoc11_2(notes: &[MonkeyNotes]) -> Result<usize> {
+    let mut monkeys: Vec<Monkey> = notes.iter().map(Monkey::new).collect();
+    let lcm = notes.iter().map(|n| n.test).product();
+    play(&mut monkeys, 10000, 2, lcm)
 }
 
-fn aoc11_2() {
-    println!("solving AOC day 11 part 2");
-    let reader = BufReader::new(File::open("input-11").unwrap());
-
-    let mut monkeys: Vec<Monkey> = vec![];
-    let mut monkey_id: Option<usize> = None;
-    let mut starting_items: String = String::from("");
-    let mut operation: String = String::from("");
-    let mut test: Option<isize> = None;
-    let mut true_target: Option<usize> = None;
-    let mut false_target: Option<usize> = None;
-    let mut lcm = 1;
-
-    for (_index, line) in reader.lines().enumerate() {
-        let line = line.unwrap();
-        if line.starts_with("Monkey") {
-            monkey_id = Some(parse::<usize>(line.substring(7, line.len() - 1)).unwrap());
-            continue;
-        }
-        if line.starts_with("  Starting items: ") {
-            starting_items = line.substring(18, line.len()).to_string();
-            continue;
-        }
-        if line.starts_with("  Operation: new = ") {
-            operation = line.substring(19, line.len()).to_string();
-            continue;
-        }
-        if line.starts_with("  Test: divisible by ") {
-            test = Some(parse::<isize>(line.substring(21, line.len())).unwrap());
-            continue;
-        }
-        if line.starts_with("    If true: throw to monkey ") {
-            true_target = Some(parse::<usize>(line.substring(29, line.len())).unwrap());
-            continue;
-        }
-        if line.starts_with("    If false: throw to monkey ") {
-            false_target = Some(parse::<usize>(line.substring(30, line.len())).unwrap());
-            continue;
-        }
-        if line == "" {
-            monkeys.push(Monkey::new(
-                monkey_id.unwrap(),
-                starting_items.to_string(),
-                operation.to_string(),
-                test.unwrap(),
-                true_target.unwrap(),
-                false_target.unwrap(),
-            ));
-            lcm = lcm * test.unwrap();
-            continue;
-        }
-    }
+pub struct Aoc11;
 
-    for _n in 0..10000 {
-        for m in 0..monkeys.len() {
-            let current_monkey = monkeys.get_mut(m).unwrap();
-            let actions = current_monkey.turn(2, lcm);
-            for action in actions {
-                monkeys
-                    .get_mut(action.target)
-                    .unwrap()
-                    .items
-                    .add(action.item)
-                    .map_err(|err| println!("{:?}", err))
-                    .ok();
+impl Solver for Aoc11 {
+    type Input = Vec<MonkeyNotes>;
+
+    fn parse(&self, input: &str) -> Result<Vec<MonkeyNotes>> {
+        let notes = input::split_records(input)
+            .map(|record| MonkeyNotes::new(&record))
+            .collect::<Result<Vec<MonkeyNotes>>>()?;
+
+        for n in notes.iter() {
+            for target in [n.true_target, n.false_target] {
+                if target >= notes.len() {
+                    return Err(Error::NoSolution(format!(
+                        "there is no monkey {} to throw to",
+                        target
+                    )));
+                }
             }
         }
+
+        Ok(notes)
     }
 
-    monkeys.sort_by(|a, b| b.items_seen.cmp(&a.items_seen));
+    fn part1(&self, notes: &Vec<MonkeyNotes>) -> Result<Answer> {
+        Ok(aoc11_1(notes)?.into())
+    }
 
-    for monkey in monkeys.iter() {
-        println!("{} {}", monkey.id, monkey.items_seen);
+    fn part2(&self, notes: &Vec<MonkeyNotes>) -> Result<Answer> {
+        Ok(aoc11_2(notes)?.into())
     }
+}
 
-    let max = monkeys.get(0).unwrap().items_seen;
-    let max2 = monkeys.get(1).unwrap().items_seen;
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    println!("{} * {} = {}", max, max2, max * max2);
-}
+    const EXAMPLE: &str = "\
+Monkey 0:
+  Starting items: 79, 98
+  Operation: new = old * 19
+  Test: divisible by 23
+    If true: throw to monkey 2
+    If false: throw to monkey 3
+
+Monkey 1:
+  Starting items: 54, 65, 75, 74
+  Operation: new = old + 6
+  Test: divisible by 19
+    If true: throw to monkey 2
+    If false: throw to monkey 0
+
+Monkey 2:
+  Starting items: 79, 60, 97
+  Operation: new = old * old
+  Test: divisible by 13
+    If true: throw to monkey 1
+    If false: throw to monkey 3
+
+Monkey 3:
+  Starting items: 74
+  Operation: new = old + 3
+  Test: divisible by 17
+    If true: throw to monkey 0
+    If false: throw to monkey 1
 
-pub fn aoc11() {
-    aoc11_1();
-    aoc11_2();
+";
+
+    #[test]
+    fn example() {
+        let notes = Aoc11.parse(EXAMPLE).unwrap();
+        assert_eq!(Aoc11.part1(&notes).unwrap(), Answer::from(10605));
+        assert_eq!(Aoc11.part2(&notes).unwrap(), Answer::from(2713310158_i64));
+    }
+
+    #[test]
+    fn no_trailing_blank_line() {
+        let notes = Aoc11.parse(EXAMPLE.trim_end()).unwrap();
+        assert_eq!(notes.len(), 4);
+        assert!(Aoc11.parse("Monkey 0:\n  Test: divisible by 3\n").is_err());
+        let divide_by_zero = EXAMPLE.replacen("old * 19", "old / 0", 1);
+        assert!(matches!(
+            Aoc11.parse(&divide_by_zero),
+            Err(Error::Parse {
+                line: 3,
+                column: 26,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn single_monkey() {
+        let notes = Aoc11
+            .parse(
+                "Monkey 0:
+  Starting items: 79, 98
+  Operation: new = old * 19
+  Test: divisible by 23
+    If true: throw to monkey 0
+    If false: throw to monkey 0
+",
+            )
+            .unwrap();
+        assert!(matches!(Aoc11.part1(&notes), Err(Error::NoSolution(_))));
+        assert!(matches!(Aoc11.part2(&notes), Err(Error::NoSolution(_))));
+    }
 }
@@ -1,106 +1,72 @@
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use crate::error::{Error, Result};
+use crate::grid::{Grid, NEIGHBOURS4};
+use crate::solver::{Answer, Solver};
 
 #[derive(Debug, Clone)]
-struct Visit {
-    n: usize,
-}
-
-#[derive(Debug, Clone)]
-struct Map {
-    n_cols: usize,
-    elevation: Vec<Vec<isize>>,
-    visits: Vec<Vec<Visit>>,
+pub struct Map {
+    target: (usize, usize),
+    elevation: Grid<isize>,
+    visits: Grid<usize>,
 }
 
 impl Map {
-    fn new() -> Map {
-        Map {
-            n_cols: 0,
-            elevation: vec![],
-            visits: vec![],
-        }
-    }
-    fn elevation_value(&self, x: usize, y: usize) -> isize {
-        self.elevation.get(y).unwrap().get(x).unwrap().clone()
-    }
-
-    fn visibility_value(&self, x: usize, y: usize) -> usize {
-        self.visits.get(y).unwrap().get(x).unwrap().n.clone()
-    }
-
     fn try_update(&mut self, x: usize, y: usize, value: usize) -> bool {
-        if self.visibility_value(x, y) > value {
-            self.visits.get_mut(y).unwrap().get_mut(x).unwrap().n = value;
+        if self.visits[(x, y)] > value {
+            self.visits[(x, y)] = value;
             true
         } else {
             false
         }
     }
 
-    fn check(&mut self, x: usize, y: usize) {
-        if self.visibility_value(x, y) < usize::MAX {
-            let current_elevation = self.elevation_value(x, y);
-            let next_visit_value = self.visibility_value(x, y) + 1;
-
-            if x > 0 {
-                if self.elevation_value(x - 1, y) <= current_elevation + 1 {
-                    self.try_update(x - 1, y, next_visit_value);
-                }
-            }
-            if x < self.n_cols - 1 {
-                if self.elevation_value(x + 1, y) <= current_elevation + 1 {
-                    self.try_update(x + 1, y, next_visit_value);
-                }
-            }
-            if y > 0 {
-                if self.elevation_value(x, y - 1) <= current_elevation + 1 {
-                    self.try_update(x, y - 1, next_visit_value);
-                }
-            }
-            if y < self.visits.len() - 1 {
-                if self.elevation_value(x, y + 1) <= current_elevation + 1 {
-                    self.try_update(x, y + 1, next_visit_value);
+    /// relaxes the neighbours of `(x, y)`, returns whether any of them improved
+    fn check(&mut self, x: usize, y: usize) -> bool {
+        let mut updated = false;
+        if self.visits[(x, y)] < usize::MAX {
+            let current_elevation = self.elevation[(x, y)];
+            let next_visit_value = self.visits[(x, y)] + 1;
+
+            for (dx, dy) in NEIGHBOURS4 {
+                if let Some((nx, ny)) = self.elevation.offset(x, y, dx, dy) {
+                    if self.elevation[(nx, ny)] <= current_elevation + 1 {
+                        updated |= self.try_update(nx, ny, next_visit_value);
+                    }
                 }
             }
         }
+        updated
     }
 
-    fn run(&mut self) -> usize {
-        let mut runs = 0;
-        while runs < 1000 {
-            runs += 1;
-            for y in 0..self.elevation.len() {
-                for x in 0..self.elevation.get(y).unwrap().len() {
-                    if self.visibility_value(x, y) < usize::MAX {
-                        if self.elevation_value(x, y) == 26 {
-                            return self.visibility_value(x, y);
-                        }
-                        self.check(x, y);
-                    }
-                }
+    fn run(&mut self) -> Result<usize> {
+        let mut updated = true;
+        while updated {
+            updated = false;
+            for (x, y) in self.elevation.positions() {
+                updated |= self.check(x, y);
             }
         }
-        println!("didn't find a solution: {:?}", self.visits);
-        panic!();
+        match self.visits[self.target] {
+            usize::MAX => Err(Error::no_solution("cannot reach the best signal")),
+            n => Ok(n),
+        }
     }
 
     #[allow(dead_code)]
     fn print(&self) {
         println!();
         println!("ELEVATIONS");
-        for y in 0..self.elevation.len() {
-            for x in 0..self.elevation.get(y).unwrap().len() {
-                print!("{:>4}", self.elevation_value(x, y));
+        for row in self.elevation.rows() {
+            for elevation in row {
+                print!("{:>4}", elevation);
             }
             println!();
         }
         println!();
         println!("VISITS");
-        for y in 0..self.elevation.len() {
-            for x in 0..self.elevation.get(y).unwrap().len() {
-                if self.visibility_value(x, y) < usize::MAX {
-                    print!("{:>4}", self.visibility_value(x, y));
+        for row in self.visits.rows() {
+            for visits in row {
+                if *visits < usize::MAX {
+                    print!("{:>4}", visits);
                 } else {
                     print!("    ");
                 }
@@ -110,74 +76,68 @@ impl Map {
     }
 }
 
-fn aoc12_1() {
-    println!("solving AOC day 12 part 1");
-    let reader = BufReader::new(File::open("input-12").unwrap());
-
-    let mut map = Map::new();
+fn aoc12_1(map: &Map) -> Result<usize> {
+    let mut map = map.clone();
+    map.run()
+}
 
-    for (_index, line) in reader.lines().enumerate() {
-        let line = line.unwrap();
-        if map.n_cols < 1 {
-            map.n_cols = line.len();
+fn aoc12_2(map: &Map) -> Result<usize> {
+    let mut map = map.clone();
+    for (x, y) in map.elevation.positions() {
+        if map.elevation[(x, y)] == 0 {
+            map.try_update(x, y, 0);
         }
-        map.elevation.push(
-            line.chars()
-                .map(|c| match c {
-                    'S' => 0,
-                    'E' => 26,
-                    _ => c as isize - 97,
-                })
-                .collect(),
-        );
-        map.visits.push(
-            line.chars()
-                .map(|c| match c {
-                    'S' => Visit { n: 0 },
-                    _ => Visit { n: usize::MAX },
-                })
-                .collect(),
-        );
     }
-
-    println!("solution: {:?}", map.run());
+    map.run()
 }
 
-fn aoc12_2() {
-    println!("solving AOC day 12 part 2");
-    let reader = BufReader::new(File::open("input-12").unwrap());
-
-    let mut map = Map::new();
+pub struct Aoc12;
+
+impl Solver for Aoc12 {
+    type Input = Map;
+
+    fn parse(&self, input: &str) -> Result<Map> {
+        let squares = Grid::parse(input, |c| matches!(c, 'a'..='z' | 'S' | 'E').then_some(c))?;
+        let target = squares
+            .find(|c| *c == 'E')
+            .ok_or_else(|| Error::no_solution("the map has no best signal E"))?;
+
+        Ok(Map {
+            target,
+            elevation: squares.map(|c| match c {
+                'S' => 0,
+                'E' => 25,
+                _ => *c as isize - 97,
+            }),
+            visits: squares.map(|c| if *c == 'S' { 0 } else { usize::MAX }),
+        })
+    }
 
-    for (_index, line) in reader.lines().enumerate() {
-        let line = line.unwrap();
-        if map.n_cols < 1 {
-            map.n_cols = line.len();
-        }
-        map.elevation.push(
-            line.chars()
-                .map(|c| match c {
-                    'S' => 0,
-                    'E' => 26,
-                    _ => c as isize - 97,
-                })
-                .collect(),
-        );
-        map.visits.push(
-            line.chars()
-                .map(|c| match c {
-                    'S' => Visit { n: 0 },
-                    'a' => Visit { n: 0 },
-                    _ => Visit { n: usize::MAX },
-                })
-                .collect(),
-        );
+    fn part1(&self, map: &Map) -> Result<Answer> {
+        Ok(aoc12_1(map)?.into())
     }
 
-    println!("solution: {:?}", map.run());
+    fn part2(&self, map: &Map) -> Result<Answer> {
+        Ok(aoc12_2(map)?.into())
+    }
 }
 
-pub fn aoc12() {
-    aoc12_1();
-    aoc12_2();
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+Sabqponm
+abcryxxl
+accszExk
+acctuvwj
+abdefghi
+";
+
+    #[test]
+    fn example() {
+        let map = Aoc12.parse(EXAMPLE).unwrap();
+        assert_eq!(Aoc12.part1(&map).unwrap(), Answer::from(31));
+        assert_eq!(Aoc12.part2(&map).unwrap(), Answer::from(29));
+    }
 }
@@ -1,147 +1,106 @@
 use std::cmp::Ordering;
 use std::fmt;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
 
-const RADIX: u32 = 10;
+use crate::error::{self, Error, Result};
+use crate::input;
+use crate::solver::{Answer, Solver};
 
-fn is_num(c: &char) -> bool {
-    let digit = c.clone() as isize - 48;
-    digit >= 0 && digit <= 9
+/// a value within a packet, either an integer or a list of values
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Value {
+    Integer(u32),
+    List(Vec<Value>),
 }
 
-#[derive(Debug, Clone)]
-struct Packet {
-    line: String,
-}
-
-impl fmt::Display for Packet {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.line)
-    }
-}
-
-impl Packet {
-    fn new(line: &str) -> Packet {
-        Packet {
-            line: line.to_string(),
-        }
-    }
-
-    fn compare(&self, other: &Packet) -> Ordering {
-        let numbers1: Vec<char> = self.line.chars().into_iter().collect();
-        let numbers2: Vec<char> = other.line.chars().into_iter().collect();
-
-        let mut n1 = 0;
-        let mut n2 = 0;
-
-        let mut depth1 = 0;
-        let mut depth2 = 0;
-
-        let mut compared_different_depths = false;
-
-        while n1 < numbers1.len() && n2 < numbers2.len() {
-            let char1 = numbers1.get(n1).unwrap();
-            let char2 = numbers2.get(n2).unwrap();
-
-            if depth1 == depth2 {
-                compared_different_depths = false;
-            }
-            match (char1, char2) {
-                (',', ',') => {}
-                ('[', '[') => {
-                    depth1 += 1;
-                    depth2 += 1;
+impl Value {
+    /// parses the value starting at `pos`, moving `pos` past it
+    fn parse(chars: &[char], pos: &mut usize, index: usize) -> Result<Value> {
+        let error = |pos: usize, message: &str| Error::parse(index + 1, pos + 1, message);
+
+        match chars.get(*pos) {
+            Some('[') => {
+                *pos += 1;
+                let mut values = vec![];
+                if chars.get(*pos) == Some(&']') {
+                    *pos += 1;
+                    return Ok(Value::List(values));
                 }
-                (']', ']') => {
-                    if depth1 != depth2 {
-                        if depth1 < depth2 {
-                            return Ordering::Less;
-                        } else {
-                            return Ordering::Greater;
+                loop {
+                    values.push(Value::parse(chars, pos, index)?);
+                    match chars.get(*pos) {
+                        Some(',') => *pos += 1,
+                        Some(']') => {
+                            *pos += 1;
+                            return Ok(Value::List(values));
                         }
+                        _ => return Err(error(*pos, "expected ',' or ']'")),
                     }
-                    depth1 -= 1;
-                    depth2 -= 1;
-                }
-                ('[', _) => {
-                    depth1 += 1;
-                    n2 -= 1;
-                }
-                (_, '[') => {
-                    depth2 += 1;
-                    n1 -= 1;
-                }
-                (_, ']') => {
-                    return Ordering::Greater;
                 }
-                (']', _) => {
-                    return Ordering::Less;
+            }
+            Some(c) if c.is_ascii_digit() => {
+                let start = *pos;
+                while chars.get(*pos).map(|c| c.is_ascii_digit()).unwrap_or(false) {
+                    *pos += 1;
                 }
-                (a, b) => {
-                    if compared_different_depths && depth1 != depth2 {
-                        if depth1 < depth2 {
-                            return Ordering::Less;
-                        } else {
-                            return Ordering::Greater;
-                        }
-                    }
-
-                    let mut number1 = a.to_digit(RADIX).unwrap();
-                    let mut number2 = b.to_digit(RADIX).unwrap();
-
-                    while is_num(numbers1.get(n1 + 1).unwrap()) {
-                        number1 =
-                            number1 * 10 + numbers1.get(n1 + 1).unwrap().to_digit(RADIX).unwrap();
-                        n1 = n1 + 1;
-                    }
-                    while is_num(numbers2.get(n2 + 1).unwrap()) {
-                        number2 =
-                            number2 * 10 + numbers2.get(n2 + 1).unwrap().to_digit(RADIX).unwrap();
-                        n2 = n2 + 1;
-                    }
-
-                    if number1 < number2 {
-                        return Ordering::Less;
-                    } else if number1 > number2 {
-                        return Ordering::Greater;
-                    }
+                let number: String = chars[start..*pos].iter().collect();
+                Ok(Value::Integer(error::number::<u32>(&number, index, start)?))
+            }
+            _ => Err(error(*pos, "expected '[' or a number")),
+        }
+    }
 
-                    if depth1 != depth2 {
-                        compared_different_depths = true;
+    fn compare(&self, other: &Value) -> Ordering {
+        match (self, other) {
+            (Value::Integer(a), Value::Integer(b)) => a.cmp(b),
+            (Value::List(a), Value::List(b)) => {
+                for (x, y) in a.iter().zip(b.iter()) {
+                    let ordering = x.compare(y);
+                    if ordering != Ordering::Equal {
+                        return ordering;
                     }
                 }
+                a.len().cmp(&b.len())
             }
-            n1 += 1;
-            n2 += 1;
+            (Value::Integer(a), list) => Value::List(vec![Value::Integer(*a)]).compare(list),
+            (list, Value::Integer(b)) => list.compare(&Value::List(vec![Value::Integer(*b)])),
         }
-
-        if n1 == numbers1.len() && n2 == numbers2.len() {
-            return Ordering::Equal;
-        }
-        if n1 == numbers1.len() {
-            return Ordering::Less;
-        }
-        if n2 == numbers2.len() {
-            return Ordering::Greater;
-        }
-        panic!("wtf {} {} {:?} {:?}", n1, n2, numbers1, numbers2)
     }
 }
 
-fn aoc13_1() {
-    println!("solving AOC day 13 part 1");
-    let reader = BufReader::new(File::open("input-13").unwrap());
+#[derive(Debug, Clone)]
+pub struct Packet {
+    line: String,
+    value: Value,
+}
 
-    let mut packets: Vec<Packet> = vec![];
+impl fmt::Display for Packet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.line)
+    }
+}
 
-    for (_index, line) in reader.lines().enumerate() {
-        let line = line.unwrap();
-        if line != "" {
-            packets.push(Packet::new(&line));
+impl Packet {
+    /// parses the packet in the line with 0-based `index`
+    pub fn new(line: &str, index: usize) -> Result<Packet> {
+        let chars: Vec<char> = line.chars().collect();
+        let mut pos = 0;
+        let value = Value::parse(&chars, &mut pos, index)?;
+        if pos < chars.len() {
+            return Err(Error::parse(index + 1, pos + 1, "trailing characters"));
         }
+        Ok(Packet {
+            line: line.to_string(),
+            value,
+        })
     }
 
+    /// orders two packets, `Less` means they are in the right order
+    pub fn compare(&self, other: &Packet) -> Ordering {
+        self.value.compare(&other.value)
+    }
+}
+
+fn aoc13_1(packets: &[Packet]) -> usize {
     let mut sum = 0;
     let mut pair = 0;
     while pair + 1 < packets.len() {
@@ -153,37 +112,121 @@ fn aoc13_1() {
         pair += 2;
     }
 
-    println!("solution: {}", sum);
+    sum
+}
+
+fn aoc13_2(packets: &[Packet]) -> usize {
+    // the position of a divider in the sorted list is given by the number of packets ordered before it
+    let divider1 = Packet::new("[[2]]", 0).expect("valid divider packet");
+    let divider2 = Packet::new("[[6]]", 0).expect("valid divider packet");
+
+    let before1 = packets
+        .iter()
+        .filter(|p| p.compare(&divider1) == Ordering::Less)
+        .count();
+    let before2 = packets
+        .iter()
+        .filter(|p| p.compare(&divider2) == Ordering::Less)
+        .count();
+
+    (before1 + 1) * (before2 + 2)
 }
 
-fn aoc13_2() {
-    println!("solving AOC day 13 part 2");
-    let reader = BufReader::new(File::open("input-13").unwrap());
+pub struct Aoc13;
 
-    let mut packets: Vec<Packet> = vec![];
+impl Solver for Aoc13 {
+    type Input = Vec<Packet>;
 
-    for (_index, line) in reader.lines().enumerate() {
-        let line = line.unwrap();
-        if line == "" {
-            continue;
+    fn parse(&self, input: &str) -> Result<Vec<Packet>> {
+        let mut packets = vec![];
+        for record in input::split_records(input) {
+            if record.lines.len() != 2 {
+                return Err(Error::line(
+                    record.index,
+                    format!("expected a pair of packets, found {}", record.lines.len()),
+                ));
+            }
+            for (index, line) in record.lines() {
+                packets.push(Packet::new(line, index)?);
+            }
         }
-        packets.push(Packet::new(&line));
+        Ok(packets)
     }
-    packets.push(Packet::new("[[2]]"));
-    packets.push(Packet::new("[[6]]"));
 
-    packets.sort_by(|a, b| a.compare(b));
+    fn part1(&self, packets: &Vec<Packet>) -> Result<Answer> {
+        Ok(aoc13_1(packets).into())
+    }
 
-    let mut solution = 1;
-    for (i, el) in packets.iter().enumerate() {
-        if el.line == "[[2]]" || el.line == "[[6]]" {
-            solution *= i + 1;
-        }
+    fn part2(&self, packets: &Vec<Packet>) -> Result<Answer> {
+        Ok(aoc13_2(packets).into())
     }
-    println!("solution: {}", solution);
 }
 
-pub fn aoc13() {
-    aoc13_1();
-    aoc13_2();
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+[1,1,3,1,1]
+[1,1,5,1,1]
+
+[[1],[2,3,4]]
+[[1],4]
+
+[9]
+[[8,7,6]]
+
+[[4,4],4,4]
+[[4,4],4,4,4]
+
+[7,7,7,7]
+[7,7,7]
+
+[]
+[3]
+
+[[[]]]
+[[]]
+
+[1,[2,[3,[4,[5,6,7]]]],8,9]
+[1,[2,[3,[4,[5,6,0]]]],8,9]
+";
+
+    #[test]
+    fn example() {
+        let packets = Aoc13.parse(EXAMPLE).unwrap();
+        assert_eq!(Aoc13.part1(&packets).unwrap(), Answer::from(13));
+        assert_eq!(Aoc13.part2(&packets).unwrap(), Answer::from(140));
+    }
+
+    #[test]
+    fn compare() {
+        let packets = Aoc13.parse(EXAMPLE).unwrap();
+        let expected = [
+            Ordering::Less,
+            Ordering::Less,
+            Ordering::Greater,
+            Ordering::Less,
+            Ordering::Greater,
+            Ordering::Less,
+            Ordering::Greater,
+            Ordering::Greater,
+        ];
+        for (pair, ordering) in packets.chunks(2).zip(expected) {
+            assert_eq!(
+                pair[0].compare(&pair[1]),
+                ordering,
+                "{} vs {}",
+                pair[0],
+                pair[1]
+            );
+            assert_eq!(pair[1].compare(&pair[0]), ordering.reverse());
+        }
+
+        let a = Packet::new("[[2]]", 0).unwrap();
+        let b = Packet::new("[2]", 0).unwrap();
+        assert_eq!(a.compare(&b), Ordering::Equal);
+        let c = Packet::new("[10]", 0).unwrap();
+        assert_eq!(b.compare(&c), Ordering::Less);
+    }
 }
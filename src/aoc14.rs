@@ -1,7 +1,9 @@
-use parse_int::parse;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
 use std::{fmt, thread, time};
+
+use crate::error::{self, Error, Result};
+use crate::grid::{BoundingBox, Grid};
+use crate::solver::{Answer, Solver};
+
 #[derive(Debug, Clone)]
 struct Position {
     x: usize,
@@ -9,12 +11,15 @@ struct Position {
 }
 
 impl Position {
-    fn new(line: &str) -> Position {
-        let mut tokens = line.split(",");
-        Position {
-            x: parse::<usize>(tokens.next().unwrap()).unwrap(),
-            y: parse::<usize>(tokens.next().unwrap()).unwrap(),
-        }
+    /// parses a `x,y` token found at `offset` in the line with `index`
+    fn new(token: &str, index: usize, offset: usize) -> Result<Position> {
+        let (x, y) = token.split_once(',').ok_or_else(|| {
+            Error::parse(index + 1, offset + 1, format!("invalid point {:?}", token))
+        })?;
+        Ok(Position {
+            x: error::number::<usize>(x, index, offset)?,
+            y: error::number::<usize>(y, index, offset + x.len() + 1)?,
+        })
     }
 
     fn equal(&self, other: &Position) -> bool {
@@ -39,7 +44,7 @@ impl Position {
     }
 }
 
-struct Line {
+pub struct Line {
     points: Vec<Position>,
 }
 
@@ -73,65 +78,48 @@ impl fmt::Display for FieldValue {
     }
 }
 
-#[derive(Debug, Clone)]
-struct Field {
-    value: FieldValue,
-}
-
 #[derive(Debug, Clone)]
 struct Map {
-    matrix: Vec<Vec<Field>>,
-    minx: usize,
-    maxx: usize,
-    miny: usize,
-    maxy: usize,
+    matrix: Grid<FieldValue>,
+    bounding_box: BoundingBox,
+    /// added to every x, so the sand can pile up left of the rocks
+    shift: usize,
+    /// the row of the floor, 2 below the lowest rock
+    floor: usize,
 }
 
 impl Map {
-    fn new() -> Map {
-        let mut matrix = vec![];
-        for y in 0..1000 {
-            let mut row = vec![];
-            for x in 0..1000 {
-                if y == 0 && x == 500 {
-                    row.push(Field {
-                        value: FieldValue::START,
-                    });
-                } else {
-                    row.push(Field {
-                        value: FieldValue::AIR,
-                    });
-                }
-            }
-            matrix.push(row);
+    /// a map just large enough for the rocks and the sand piling up on the floor
+    fn new(rock_lines: &[Line]) -> Map {
+        let mut rocks = BoundingBox::empty();
+        rocks.update(500, 0);
+        for p in rock_lines.iter().flat_map(|line| line.points.iter()) {
+            rocks.update(p.x as isize, p.y as isize);
         }
-        Map {
+        // sand on the floor spreads at most `floor` to both sides of the source
+        let floor = rocks.max_y as usize + 2;
+        let shift = floor + 1;
+        let width = (rocks.max_x as usize).max(500 + floor) + shift + 2;
+
+        let mut matrix = Grid::new(width, floor + 1, FieldValue::AIR);
+        matrix[(500 + shift, 0)] = FieldValue::START;
+        let mut bounding_box = BoundingBox::empty();
+        bounding_box.update((500 + shift) as isize, 0);
+        let mut map = Map {
             matrix,
-            minx: 500,
-            maxx: 500,
-            miny: 0,
-            maxy: 0,
+            bounding_box,
+            shift,
+            floor,
+        };
+        for rock_line in rock_lines.iter() {
+            map.draw_rocks(rock_line);
         }
+        map
     }
 
-    fn get(&self, x: usize, y: usize) -> &Field {
-        self.matrix.get(y).unwrap().get(x).unwrap()
-    }
-
-    fn put(&mut self, x: usize, y: usize, value: FieldValue) {
-        self.matrix.get_mut(y).unwrap().get_mut(x).unwrap().value = value
-    }
-
-    fn update_bounding_box(&mut self, point: &Position) {
-        if self.minx > point.x {
-            self.minx = point.x;
-        }
-        if self.maxx < point.x {
-            self.maxx = point.x;
-        }
-        if self.maxy < point.y {
-            self.maxy = point.y;
-        }
+    /// the position of the source of the sand
+    fn source(&self) -> (usize, usize) {
+        (500 + self.shift, 0)
     }
 
     fn draw_rocks(&mut self, line: &Line) {
@@ -139,45 +127,50 @@ impl Map {
             let mut p = line.get(point - 1).clone();
             let target = line.get(point);
 
-            self.update_bounding_box(&p);
-            self.update_bounding_box(&target);
+            self.bounding_box
+                .update((p.x + self.shift) as isize, p.y as isize);
+            self.bounding_box
+                .update((target.x + self.shift) as isize, target.y as isize);
 
-            while !p.equal(&target) {
-                self.put(p.x, p.y, FieldValue::ROCK);
-                p.move_towards(&target);
+            while !p.equal(target) {
+                self.matrix[(p.x + self.shift, p.y)] = FieldValue::ROCK;
+                p.move_towards(target);
             }
-            self.put(p.x, p.y, FieldValue::ROCK);
+            self.matrix[(p.x + self.shift, p.y)] = FieldValue::ROCK;
         }
     }
 
-    fn print(&self) {
-        for y in self.miny..=self.maxy {
-            for x in self.minx..=self.maxx {
-                print!("{}", self.get(x, y).value);
-            }
-            println!();
+    /// covers the whole width of the map with a floor
+    fn draw_floor(&mut self) {
+        for x in 0..self.matrix.width() {
+            self.matrix[(x, self.floor)] = FieldValue::ROCK;
         }
+        self.bounding_box.update(0, self.floor as isize);
+        self.bounding_box
+            .update(self.matrix.width() as isize - 1, self.floor as isize);
+    }
+
+    fn print(&self) {
+        let b = &self.bounding_box;
+        self.print_slice(b.min_x as usize, b.max_x as usize, b.min_y as usize);
     }
 
     fn print_slice(&self, minx: usize, maxx: usize, miny: usize) {
-        for y in miny..=self.maxy {
+        let maxx = maxx.min(self.matrix.width() - 1);
+        for y in miny..=self.bounding_box.max_y as usize {
             for x in minx..=maxx {
-                print!("{}", self.get(x, y).value);
+                print!("{}", self.matrix[(x, y)]);
             }
             println!();
         }
     }
 
     fn blocked(&self, x: usize, y: usize) -> bool {
-        match self.get(x, y).value {
-            FieldValue::SAND => true,
-            FieldValue::ROCK => true,
-            _ => false,
-        }
+        matches!(self.matrix[(x, y)], FieldValue::SAND | FieldValue::ROCK)
     }
 
     fn drop(&mut self, x: usize, y: usize) -> usize {
-        if x < self.minx || x > self.maxx || y > self.maxy {
+        if !self.bounding_box.contains(x as isize, y as isize) {
             return usize::MAX;
         }
         if self.blocked(x, y) {
@@ -192,37 +185,22 @@ impl Map {
             steps = self.drop(x + 1, y + 1);
         }
         if steps == 0 {
-            if x == 500 && y == 0 {
+            if (x, y) == self.source() {
                 return usize::MAX;
             }
-            self.put(x, y, FieldValue::SAND);
+            self.matrix[(x, y)] = FieldValue::SAND;
             return 1;
         }
         if steps == usize::MAX {
             return steps;
         }
-        return steps + 1;
+        steps + 1
     }
 }
 
-fn aoc14_1(debug: bool) {
-    println!("solving AOC day 14 part 1");
-    let reader = BufReader::new(File::open("input-14").unwrap());
-
-    let mut map = Map::new();
-
-    for (_index, line) in reader.lines().enumerate() {
-        let line = line.unwrap();
-        let mut rock_line = Line::new();
-        for token in line
-            .split(" ")
-            .filter(|t| t.len() > 2)
-            .map(|c| Position::new(c))
-        {
-            rock_line.points.push(token);
-        }
-        map.draw_rocks(&rock_line);
-    }
+fn aoc14_1(rock_lines: &[Line], debug: bool) -> usize {
+    let mut map = Map::new(rock_lines);
+    let (source_x, source_y) = map.source();
 
     if debug {
         map.print();
@@ -232,8 +210,8 @@ fn aoc14_1(debug: bool) {
     let mut steps = 0;
     let s = time::Duration::from_millis(50);
 
-    while steps < 1000 {
-        steps = map.drop(500, 0);
+    while steps != usize::MAX {
+        steps = map.drop(source_x, source_y);
         sand_units += 1;
         if debug && sand_units % 10 == 0 {
             map.print();
@@ -245,62 +223,89 @@ fn aoc14_1(debug: bool) {
     if debug {
         map.print();
     }
-    println!("sand: {}", sand_units - 1);
+    sand_units - 1
 }
 
-fn aoc14_2(debug: bool) {
-    println!("solving AOC day 14 part 2");
-    let reader = BufReader::new(File::open("input-14").unwrap());
-
-    let mut map = Map::new();
-
-    for (_index, line) in reader.lines().enumerate() {
-        let line = line.unwrap();
-        let mut rock_line = Line::new();
-        for token in line
-            .split(" ")
-            .filter(|t| t.len() > 2)
-            .map(|c| Position::new(c))
-        {
-            rock_line.points.push(token);
-        }
-        map.draw_rocks(&rock_line);
-    }
-
-    // draw bottom line
-    {
-        let maxy = map.maxy + 2;
-        let mut bottom_line = Line::new();
-        bottom_line.points.push(Position { x: 00, y: maxy });
-        bottom_line.points.push(Position { x: 999, y: maxy });
-        map.draw_rocks(&bottom_line);
-    }
+fn aoc14_2(rock_lines: &[Line], debug: bool) -> usize {
+    let mut map = Map::new(rock_lines);
+    map.draw_floor();
 
+    let (source_x, source_y) = map.source();
     if debug {
-        map.print_slice(400, 600, 0);
+        map.print_slice(source_x - 100, source_x + 100, 0);
     }
 
     let mut sand_units = 0;
     let mut steps = 0;
     let s = time::Duration::from_millis(50);
 
-    while steps < 1000 {
-        steps = map.drop(500, 0);
+    while steps != usize::MAX {
+        steps = map.drop(source_x, source_y);
         sand_units += 1;
         if debug && sand_units % 50 == 0 {
-            map.print_slice(400, 550, 0);
+            map.print_slice(source_x - 100, source_x + 50, 0);
             println!("\n\nSAND: {}", sand_units);
             thread::sleep(s);
         }
     }
 
     if debug {
-        map.print_slice(400, 550, 0);
+        map.print_slice(source_x - 100, source_x + 50, 0);
     }
-    println!("sand: {}", sand_units);
+    sand_units
 }
 
-pub fn aoc14() {
-    aoc14_1(false);
-    aoc14_2(false);
+pub struct Aoc14;
+
+impl Solver for Aoc14 {
+    type Input = Vec<Line>;
+
+    fn parse(&self, input: &str) -> Result<Vec<Line>> {
+        input
+            .lines()
+            .enumerate()
+            .map(|(index, line)| {
+                let mut rock_line = Line::new();
+                let mut offset = 0;
+                for token in line.split(" -> ") {
+                    rock_line.points.push(Position::new(token, index, offset)?);
+                    offset += token.len() + 4;
+                }
+                Ok(rock_line)
+            })
+            .collect()
+    }
+
+    fn part1(&self, rock_lines: &Vec<Line>) -> Result<Answer> {
+        Ok(aoc14_1(rock_lines, false).into())
+    }
+
+    fn part2(&self, rock_lines: &Vec<Line>) -> Result<Answer> {
+        Ok(aoc14_2(rock_lines, false).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+498,4 -> 498,6 -> 496,6
+503,4 -> 502,4 -> 502,9 -> 494,9
+";
+
+    #[test]
+    fn example() {
+        let rock_lines = Aoc14.parse(EXAMPLE).unwrap();
+        assert_eq!(Aoc14.part1(&rock_lines).unwrap(), Answer::from(24));
+        assert_eq!(Aoc14.part2(&rock_lines).unwrap(), Answer::from(93));
+    }
+
+    #[test]
+    fn map_size() {
+        // the rocks are far right of the source, the sand pile is 8 rows high
+        let rock_lines = Aoc14.parse("1500,4 -> 1500,6\n").unwrap();
+        assert_eq!(Aoc14.part1(&rock_lines).unwrap(), Answer::from(0));
+        assert_eq!(Aoc14.part2(&rock_lines).unwrap(), Answer::from(64));
+    }
 }
@@ -1,11 +1,11 @@
-use min_max::*;
-use parse_int::parse;
 use regex::Regex;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
 use std::thread::JoinHandle;
 use std::{fmt, thread};
 
+use crate::error::{self, Error, Result};
+use crate::interval::{Interval, IntervalSet};
+use crate::solver::{Answer, Solver};
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 struct Sensor {
     x: isize,
@@ -19,29 +19,36 @@ impl Sensor {
     fn dist_p(&self, other: &Position) -> usize {
         self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
     }
-    fn get_options(&self, other_sensors: &Vec<Sensor>) -> Vec<Position> {
+    /// the part of the `line` within reach of this sensor
+    fn coverage(&self, line: isize) -> Option<Interval> {
+        let reach = self.dist as isize - (self.y - line).abs();
+        (reach >= 0).then(|| Interval::new(self.x - reach, self.x + reach))
+    }
+    /// positions just outside the coverage of this sensor and not covered by any other sensor,
+    /// both coordinates between 0 and `max`
+    fn get_options(&self, other_sensors: &[Sensor], max: isize) -> Vec<Position> {
         let mut options = vec![];
         let coverage = self.dist as isize;
 
         for x in self.x - coverage - 1..=self.x + coverage + 1 {
-            if x < 0 || x > 4000000 {
+            if !(0..=max).contains(&x) {
                 continue;
             }
             let dist = self.dist(x, self.y) as isize;
             let y1 = self.y - (coverage - dist) - 1;
-            if y1 >= 0 && y1 <= 4000000 {
+            if (0..=max).contains(&y1) {
                 options.push(Position { x, y: y1 })
             }
 
             let y2 = self.y + (coverage - dist) + 1;
-            if y2 >= 0 && y2 <= 4000000 {
+            if (0..=max).contains(&y2) {
                 options.push(Position { x, y: y2 });
             }
         }
         let x: Vec<Position> = options
             .iter()
-            .filter(|o| !other_sensors.iter().any(|s| s.dist_p(o) < s.dist))
-            .map(|o| o.clone())
+            .filter(|o| !other_sensors.iter().any(|s| s.dist_p(o) <= s.dist))
+            .cloned()
             .collect();
         x
     }
@@ -66,17 +73,17 @@ impl Position {
         let coverage = self.dist_p(other) as isize;
 
         for x in self.x - coverage - 1..=self.x + coverage + 1 {
-            if x < 0 || x > 4000000 {
+            if !(0..=4000000).contains(&x) {
                 continue;
             }
             let dist = self.dist(x, self.x) as isize;
             let y1 = self.y - (coverage - dist) - 1;
-            if y1 >= 0 && y1 <= 4000000 {
+            if (0..=4000000).contains(&y1) {
                 options.push(Position { x, y: y1 })
             }
 
             let y2 = self.y + (coverage - dist) + 1;
-            if y2 >= 0 && y2 <= 4000000 {
+            if (0..=4000000).contains(&y2) {
                 options.push(Position { x, y: y2 });
             }
             // let y1 = sensor.y - (sensor_coverage - dist) - 1;
@@ -95,30 +102,7 @@ impl Position {
 }
 
 #[derive(Debug, Clone)]
-struct BoundingBox {
-    minx: isize,
-    maxx: isize,
-}
-
-impl BoundingBox {
-    fn overlaps(&self, other: &BoundingBox) -> bool {
-        let other_first = self.minx <= other.maxx && self.maxx >= other.minx;
-        let this_first = other.minx <= self.maxx && other.maxx >= self.minx;
-        other_first || this_first
-    }
-
-    fn extend(&mut self, other: &BoundingBox) {
-        self.minx = min!(self.minx, other.minx);
-        self.maxx = max!(self.maxx, other.maxx);
-    }
-
-    fn size(&self) -> isize {
-        return self.maxx - self.minx;
-    }
-}
-
-#[derive(Debug, Clone)]
-struct Map {
+pub struct Map {
     sensors: Vec<Sensor>,
     beacons: Vec<Position>,
     minx: isize,
@@ -139,88 +123,54 @@ impl Map {
         }
     }
 
-    fn set(&mut self, line: &str) {
+    fn set(&mut self, line: &str, index: usize) -> Result<()> {
         let re = Regex::new(
             r"^Sensor at x=([-\d]+), y=([-\d]+): closest beacon is at x=([-\d]+), y=([-\d]+)$",
         )
         .unwrap();
-        for cap in re.captures_iter(line) {
-            let sx = parse::<isize>(&cap[1]).unwrap();
-            let sy = parse::<isize>(&cap[2]).unwrap();
-            let bx = parse::<isize>(&cap[3]).unwrap();
-            let by = parse::<isize>(&cap[4]).unwrap();
-            let beacon = Position { x: bx, y: by };
-
-            self.sensors.push(Sensor {
-                x: sx,
-                y: sy,
-                dist: beacon.dist(sx, sy),
-            });
-            self.beacons.push(beacon);
+        let cap = re
+            .captures(line)
+            .ok_or_else(|| Error::line(index, format!("invalid sensor report {:?}", line)))?;
+        let mut numbers = vec![];
+        for group in 1..=4 {
+            let m = cap.get(group).unwrap();
+            numbers.push(error::number::<isize>(m.as_str(), index, m.start())?);
         }
+        let (sx, sy) = (numbers[0], numbers[1]);
+        let beacon = Position {
+            x: numbers[2],
+            y: numbers[3],
+        };
+
+        self.sensors.push(Sensor {
+            x: sx,
+            y: sy,
+            dist: beacon.dist(sx, sy),
+        });
+        self.beacons.push(beacon);
+        Ok(())
     }
 
-    fn beacon_absense(&self, line: isize) -> isize {
-        let mut handles: Vec<JoinHandle<Option<BoundingBox>>> = vec![];
-        let mut coverage: Vec<BoundingBox> = vec![];
-
-        for s in 0..self.sensors.len() {
-            let sensor = self.sensors.get(s).unwrap().clone();
-            let beacon = self.beacons.get(s).unwrap().clone();
-            handles.push(thread::spawn(move || {
-                let sensor_coverage = sensor.dist_p(&beacon) as isize;
-
-                let mut cov_start: Option<isize> = None;
-                for x in sensor.x - sensor_coverage..=sensor.x + sensor_coverage {
-                    if sensor.dist(x, line) as isize <= sensor_coverage {
-                        if cov_start == None {
-                            cov_start = Some(x)
-                        }
-                    } else {
-                        if let Some(c) = cov_start {
-                            return Some(BoundingBox {
-                                minx: c,
-                                maxx: x - 1,
-                            });
-                        }
-                    }
-                }
-                return None;
-            }));
-        }
-
-        while handles.len() > 0 {
-            let handle = handles.remove(0);
-            if let Some(handle_coverage) = handle.join().unwrap() {
-                coverage.push(handle_coverage.clone());
-            }
-        }
-        coverage.sort_by(|a, b| a.minx.cmp(&b.minx));
-
-        let mut size: isize = 0;
-        let mut coverage2: Vec<BoundingBox> = vec![coverage.get(0).unwrap().clone()];
-        let mut pushed = false;
-
-        for c in 1..coverage.len() {
-            let cov = coverage.get(c).unwrap();
-            let l = coverage2.len();
-            let last_c = coverage2.get_mut(l - 1).unwrap();
-            if last_c.overlaps(&cov) {
-                last_c.extend(cov);
-                pushed = false;
-            } else {
-                size += last_c.size();
-                pushed = true;
-                coverage2.push(cov.clone());
-            }
-        }
-
-        if !pushed {
-            let last_c = coverage2.get(coverage2.len() - 1).unwrap();
-            size += last_c.size();
-        }
+    /// all positions of the `line` that are covered by some sensor
+    fn coverage(&self, line: isize) -> IntervalSet {
+        self.sensors
+            .iter()
+            .filter_map(|s| s.coverage(line))
+            .collect()
+    }
 
-        return size;
+    /// number of positions of the `line` that cannot hold a beacon
+    fn beacon_absense(&self, line: isize) -> usize {
+        let coverage = self.coverage(line);
+        let mut beacons: Vec<isize> = self
+            .beacons
+            .iter()
+            .filter(|b| b.y == line && coverage.contains(b.x))
+            .map(|b| b.x)
+            .collect();
+        beacons.sort();
+        beacons.dedup();
+        coverage.len() - beacons.len()
     }
 
     fn covered(&self, x: isize, y: isize) -> bool {
@@ -241,28 +191,26 @@ impl Map {
         false
     }
 
-    fn find_distress_beacon(&self) -> isize {
+    fn find_distress_beacon(&self, max: isize) -> Result<isize> {
         let mut handles: Vec<JoinHandle<Vec<Position>>> = vec![];
 
         for s in 0..self.sensors.len() {
             let sensor = self.sensors.get(s).unwrap().clone();
             let all_sensors = self.sensors.clone();
-            handles.push(thread::spawn(move || {
-                return sensor.get_options(&all_sensors);
-            }));
+            handles.push(thread::spawn(move || sensor.get_options(&all_sensors, max)));
         }
 
-        while handles.len() > 0 {
+        while !handles.is_empty() {
             let len = handles.len();
             let handle = handles.remove(len / 2);
             let options = handle.join().unwrap();
-            if options.len() > 0 {
-                let option = options.get(0).unwrap();
-                return option.x * 4000000 + option.y;
+            if !options.is_empty() {
+                let option = options.first().unwrap();
+                return Ok(option.x * 4000000 + option.y);
             }
         }
 
-        panic!("cannot find distress signal");
+        Err(Error::no_solution("cannot find distress signal"))
     }
 
     fn get(&self, x: isize, y: isize) -> char {
@@ -279,7 +227,7 @@ impl Map {
         if self.covered(x, y) {
             return '#';
         }
-        return '.';
+        '.'
     }
 
     #[allow(dead_code)]
@@ -308,36 +256,65 @@ impl fmt::Display for Map {
         write!(f, "{} {} {} {}", self.minx, self.maxx, self.miny, self.maxy)
     }
 }
-fn aoc15_1(map: &Map) {
-    println!(
-        "--solving AOC day 15 part 1\nsolution {}",
-        map.beacon_absense(2000000)
-    );
+fn aoc15_1(map: &Map) -> usize {
+    map.beacon_absense(2000000)
 }
 
-fn aoc15_2(map: &Map) {
-    println!(
-        "--solving AOC day 15 part 2\ndistress sig {}",
-        map.find_distress_beacon()
-    );
+fn aoc15_2(map: &Map) -> Result<isize> {
+    map.find_distress_beacon(4000000)
 }
 
-pub fn aoc15() {
-    let reader = BufReader::new(File::open("input-15").unwrap());
-    let mut map = Map::new();
-    for (_index, line) in reader.lines().enumerate() {
-        let line = line.unwrap();
-        map.set(&line);
+pub struct Aoc15;
+
+impl Solver for Aoc15 {
+    type Input = Map;
+
+    fn parse(&self, input: &str) -> Result<Map> {
+        let mut map = Map::new();
+        for (index, line) in input.lines().enumerate() {
+            map.set(line, index)?;
+        }
+        Ok(map)
     }
 
-    let map2 = map.clone();
+    fn part1(&self, map: &Map) -> Result<Answer> {
+        Ok(aoc15_1(map).into())
+    }
 
-    let a = thread::spawn(move || {
-        aoc15_1(&map);
-    });
-    let b = thread::spawn(move || {
-        aoc15_2(&map2);
-    });
-    a.join().unwrap();
-    b.join().unwrap();
+    fn part2(&self, map: &Map) -> Result<Answer> {
+        Ok(aoc15_2(map)?.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+Sensor at x=2, y=18: closest beacon is at x=-2, y=15
+Sensor at x=9, y=16: closest beacon is at x=10, y=16
+Sensor at x=13, y=2: closest beacon is at x=15, y=3
+Sensor at x=12, y=14: closest beacon is at x=10, y=16
+Sensor at x=10, y=20: closest beacon is at x=10, y=16
+Sensor at x=14, y=17: closest beacon is at x=10, y=16
+Sensor at x=8, y=7: closest beacon is at x=2, y=10
+Sensor at x=2, y=0: closest beacon is at x=2, y=10
+Sensor at x=0, y=11: closest beacon is at x=2, y=10
+Sensor at x=20, y=14: closest beacon is at x=25, y=17
+Sensor at x=17, y=20: closest beacon is at x=21, y=22
+Sensor at x=16, y=7: closest beacon is at x=15, y=3
+Sensor at x=14, y=3: closest beacon is at x=15, y=3
+Sensor at x=20, y=1: closest beacon is at x=15, y=3
+";
+
+    #[test]
+    fn example() {
+        let map = Aoc15.parse(EXAMPLE).unwrap();
+        assert_eq!(map.beacon_absense(10), 26);
+        assert_eq!(
+            map.coverage(11).gaps(Interval::new(0, 20)),
+            IntervalSet::from_iter([Interval::new(14, 14)])
+        );
+        assert_eq!(map.find_distress_beacon(20).unwrap(), 56000011);
+    }
 }
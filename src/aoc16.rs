@@ -1,16 +1,16 @@
 use min_max::*;
-use parse_int::parse;
 use petgraph::algo::dijkstra;
 use petgraph::prelude::*;
 use petgraph::Graph;
 use priority_queue::PriorityQueue;
 use regex::Regex;
 use std::collections::HashMap;
-use std::fs::File;
 use std::hash::Hash;
-use std::io::{BufRead, BufReader};
-use std::time::Instant;
 
+use crate::error::{self, Error, Result};
+use crate::solver::{Answer, Solver};
+
+#[derive(Clone)]
 struct Valve {
     id: String,
     pet: NodeIndex,
@@ -34,6 +34,15 @@ struct NextOption {
 }
 
 impl NextOption {
+    /// stay at `position` until the time is up, nothing is left to open
+    fn idle(position: NodeIndex) -> NextOption {
+        NextOption {
+            position,
+            rate: 0,
+            wait: 26,
+        }
+    }
+
     fn invalidate(&self) -> NextOption {
         NextOption {
             position: self.position,
@@ -75,7 +84,7 @@ impl ElephantTrial {
 
     fn get_options(
         &self,
-        nodes: &HashMap<NodeIndex, &Valve>,
+        nodes: &HashMap<NodeIndex, Valve>,
         distances: &HashMap<(NodeIndex, NodeIndex), i32>,
         start: &NodeIndex,
     ) -> Vec<ElephantTrial> {
@@ -88,7 +97,7 @@ impl ElephantTrial {
         let mut new_open = self.open.clone();
         let mut new_flow_rate = self.flow_rate;
 
-        let my_options: Vec<NextOption> = if self.my_direction.wait == 0 {
+        let mut my_options: Vec<NextOption> = if self.my_direction.wait == 0 {
             if self.my_direction.position != *start {
                 new_open.push(self.my_direction.position);
                 new_flow_rate += self.my_direction.rate;
@@ -111,7 +120,7 @@ impl ElephantTrial {
             vec![self.my_direction.clone()]
         };
 
-        let elephant_options: Vec<NextOption> = if self.elephant_direction.wait == 0 {
+        let mut elephant_options: Vec<NextOption> = if self.elephant_direction.wait == 0 {
             if self.elephant_direction.position != *start {
                 new_open.push(self.elephant_direction.position);
                 new_flow_rate += self.elephant_direction.rate;
@@ -133,6 +142,12 @@ impl ElephantTrial {
         } else {
             vec![self.elephant_direction.clone()]
         };
+        if my_options.is_empty() {
+            my_options.push(NextOption::idle(self.my_direction.position));
+        }
+        if elephant_options.is_empty() {
+            elephant_options.push(NextOption::idle(self.elephant_direction.position));
+        }
 
         for my_direction in my_options.iter() {
             for elephant_direction in elephant_options.iter() {
@@ -149,14 +164,14 @@ impl ElephantTrial {
             }
         }
 
-        if options.len() < 1 {
+        if options.is_empty() {
             options.push(self.clone());
         }
 
-        return options;
+        options
     }
 
-    fn print(&self, nodes: &HashMap<NodeIndex, &Valve>) {
+    fn print(&self, nodes: &HashMap<NodeIndex, Valve>) {
         println!(
             "found better solution {} = {} @ {}: {:?}",
             self.score,
@@ -170,7 +185,7 @@ impl ElephantTrial {
     }
 }
 
-fn print_solution(score: i32, solution: &Trial, nodes: &HashMap<NodeIndex, &Valve>) {
+fn print_solution(score: i32, solution: &Trial, nodes: &HashMap<NodeIndex, Valve>) {
     println!(
         "found better solution {} ({} = {} @ {}) {:?}",
         score,
@@ -186,11 +201,11 @@ fn print_solution(score: i32, solution: &Trial, nodes: &HashMap<NodeIndex, &Valv
 }
 
 fn find_paths(
-    nodes: &HashMap<NodeIndex, &Valve>,
+    nodes: &HashMap<NodeIndex, Valve>,
     distances: &HashMap<(NodeIndex, NodeIndex), i32>,
     start: &NodeIndex,
     debug: bool,
-) {
+) -> Result<i32> {
     let mut queue: PriorityQueue<Trial, i32> = PriorityQueue::new();
     queue.push(
         Trial {
@@ -205,7 +220,7 @@ fn find_paths(
 
     let mut best_solution: Option<Trial> = None;
 
-    while queue.len() > 0 {
+    while !queue.is_empty() {
         let current_trial = queue.pop().unwrap();
 
         if current_trial.0.time >= 30 {
@@ -271,18 +286,20 @@ fn find_paths(
         queue.push(trial, score);
     }
 
-    if let Some(best) = &best_solution {
+    let best = best_solution.ok_or_else(|| Error::no_solution("no path through the valves"))?;
+    if debug {
         println!("========= best solution =========");
         print_solution(best.score, &best, nodes);
     }
+    Ok(best.score)
 }
 
 fn find_paths_with_elephant(
-    nodes: &HashMap<NodeIndex, &Valve>,
+    nodes: &HashMap<NodeIndex, Valve>,
     distances: &HashMap<(NodeIndex, NodeIndex), i32>,
     start: &NodeIndex,
     debug: bool,
-) {
+) -> Result<i32> {
     let mut queue: PriorityQueue<ElephantTrial, i32> = PriorityQueue::new();
     queue.push(
         ElephantTrial {
@@ -309,7 +326,7 @@ fn find_paths_with_elephant(
     let mut round = 0;
     let max_flow: i32 = nodes.values().map(|n| n.rate).sum();
 
-    while queue.len() > 0 {
+    while !queue.is_empty() {
         round += 1;
         if debug && round % 100000 == 0 {
             println!(
@@ -334,7 +351,7 @@ fn find_paths_with_elephant(
             if let Some(best) = &best_solution {
                 if score > best.score {
                     if debug {
-                        solution.print(&nodes);
+                        solution.print(nodes);
                     }
 
                     best_solution = Some(solution);
@@ -342,7 +359,7 @@ fn find_paths_with_elephant(
                 }
             } else {
                 if debug {
-                    solution.print(&nodes);
+                    solution.print(nodes);
                 }
                 best_solution = Some(solution);
             }
@@ -355,7 +372,7 @@ fn find_paths_with_elephant(
         }
 
         if current_trial.0.open.len() < nodes.len() {
-            let options = current_trial.0.get_options(&nodes, &distances, &start);
+            let options = current_trial.0.get_options(nodes, distances, start);
             for option in options.iter() {
                 queue.push(option.clone(), option.score);
             }
@@ -366,7 +383,7 @@ fn find_paths_with_elephant(
                 my_direction: current_trial.0.my_direction.invalidate(),
                 elephant_direction: current_trial.0.elephant_direction.invalidate(),
                 time: current_trial.0.time + time_left,
-                score: score,
+                score,
                 flow_rate: current_trial.0.flow_rate,
                 open: current_trial.0.open.clone(),
             };
@@ -374,53 +391,58 @@ fn find_paths_with_elephant(
         }
     }
 
-    if let Some(best) = &best_solution {
+    let best = best_solution.ok_or_else(|| Error::no_solution("no path through the valves"))?;
+    if debug {
         println!("\n\n========= best solution =========");
-        best.print(&nodes);
+        best.print(nodes);
     }
+    Ok(best.score)
 }
 
 fn aoc16_1(
-    nodes: &HashMap<NodeIndex, &Valve>,
+    nodes: &HashMap<NodeIndex, Valve>,
     distances: &HashMap<(NodeIndex, NodeIndex), i32>,
     start: &NodeIndex,
-) {
-    println!("\n\nsolving AOC day 16 part 1");
-    let current = Instant::now();
-    find_paths(&nodes, &distances, &start, false);
-    println!("Time elapsed in find_paths is: {:?}", current.elapsed());
+) -> Result<i32> {
+    find_paths(nodes, distances, start, false)
 }
 
 fn aoc16_2(
-    nodes: &HashMap<NodeIndex, &Valve>,
+    nodes: &HashMap<NodeIndex, Valve>,
     distances: &HashMap<(NodeIndex, NodeIndex), i32>,
     start: &NodeIndex,
-) {
-    println!("\n\nsolving AOC day 16 part 2");
-    let current = Instant::now();
-    find_paths_with_elephant(&nodes, &distances, &start, false);
-    println!(
-        "Time elapsed in find_paths_with_elephant is: {:?}",
-        current.elapsed()
-    );
+) -> Result<i32> {
+    find_paths_with_elephant(nodes, distances, start, false)
 }
 
-pub fn aoc16() {
-    let reader = BufReader::new(File::open("input-16").unwrap());
-    let mut graph: Graph<(), (), Directed> = Graph::new();
-    let re = Regex::new(r"^Valve ([A-Z]+) has flow rate=(\d+); tunnels? leads? to valves? (.*)$")
-        .unwrap();
-    let mut tmp_edges = vec![];
+pub struct Volcano {
+    nodes: HashMap<NodeIndex, Valve>,
+    distances: HashMap<(NodeIndex, NodeIndex), i32>,
+    start: NodeIndex,
+}
+
+pub struct Aoc16;
+
+impl Solver for Aoc16 {
+    type Input = Volcano;
 
-    let mut nodes_byid = HashMap::new();
-    let mut nodes = HashMap::new();
-    let mut start: Option<NodeIndex> = None;
+    fn parse(&self, input: &str) -> Result<Volcano> {
+        let mut graph: Graph<(), (), Directed> = Graph::new();
+        let re =
+            Regex::new(r"^Valve ([A-Z]+) has flow rate=(\d+); tunnels? leads? to valves? (.*)$")
+                .unwrap();
+        let mut tmp_edges = vec![];
 
-    for (_index, line) in reader.lines().enumerate() {
-        let line = line.unwrap();
-        for cap in re.captures_iter(&line) {
+        let mut nodes_byid = HashMap::new();
+        let mut nodes = HashMap::new();
+        let mut start: Option<NodeIndex> = None;
+
+        for (index, line) in input.lines().enumerate() {
+            let cap = re
+                .captures(line)
+                .ok_or_else(|| Error::line(index, format!("invalid valve {:?}", line)))?;
             let valve_id = &cap[1];
-            let valve_rate = parse::<i32>(&cap[2]).unwrap();
+            let valve_rate = error::number::<i32>(&cap[2], index, cap.get(2).unwrap().start())?;
             let nodeindex = graph.add_node(());
 
             if valve_id == "AA" {
@@ -436,36 +458,74 @@ pub fn aoc16() {
             nodes_byid.insert(valve_id.to_string(), nodeindex);
 
             for tunnel in cap[3].split(", ") {
-                tmp_edges.push((valve_id.to_string(), tunnel.to_string()));
+                tmp_edges.push((valve_id.to_string(), tunnel.to_string(), index));
             }
         }
-    }
 
-    let start = start.unwrap();
+        let start = start.ok_or_else(|| Error::no_solution("there is no valve AA to start at"))?;
 
-    for edge in tmp_edges.iter() {
-        let from = nodes.get(nodes_byid.get(&edge.0).unwrap()).unwrap();
-        let to = nodes.get(nodes_byid.get(&edge.1).unwrap()).unwrap();
-        graph.add_edge(from.pet, to.pet, ());
-    }
+        for edge in tmp_edges.iter() {
+            let from = nodes_byid[&edge.0];
+            let to = nodes_byid.get(&edge.1).ok_or_else(|| {
+                Error::line(edge.2, format!("tunnel leads to unknown valve {}", edge.1))
+            })?;
+            graph.add_edge(from, *to, ());
+        }
 
-    let mut relevant_nodes = HashMap::new();
-    let mut distances = HashMap::new();
+        let mut relevant_nodes = HashMap::new();
+        let mut distances = HashMap::new();
 
-    for node in nodes.iter() {
-        if node.1.rate > 0 || node.0 == &start {
-            relevant_nodes.insert(node.0.clone(), node.1);
+        for node in nodes.iter() {
+            if node.1.rate > 0 || node.0 == &start {
+                relevant_nodes.insert(*node.0, node.1.clone());
 
-            let cur_distances = dijkstra(&graph, node.1.pet, None, |_| 1);
-            for dist in cur_distances.iter().filter(|d| node.0 != d.0) {
-                let target = nodes.get(dist.0).unwrap();
-                if target.rate > 0 {
-                    distances.insert((node.1.pet, target.pet), dist.1.clone());
+                let cur_distances = dijkstra(&graph, node.1.pet, None, |_| 1);
+                for dist in cur_distances.iter().filter(|d| node.0 != d.0) {
+                    let target = nodes.get(dist.0).unwrap();
+                    if target.rate > 0 {
+                        distances.insert((node.1.pet, target.pet), *dist.1);
+                    }
                 }
             }
         }
+
+        Ok(Volcano {
+            nodes: relevant_nodes,
+            distances,
+            start,
+        })
     }
 
-    aoc16_1(&relevant_nodes, &distances, &start);
-    aoc16_2(&relevant_nodes, &distances, &start);
+    fn part1(&self, volcano: &Volcano) -> Result<Answer> {
+        Ok(aoc16_1(&volcano.nodes, &volcano.distances, &volcano.start)?.into())
+    }
+
+    fn part2(&self, volcano: &Volcano) -> Result<Answer> {
+        Ok(aoc16_2(&volcano.nodes, &volcano.distances, &volcano.start)?.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+Valve AA has flow rate=0; tunnels lead to valves DD, II, BB
+Valve BB has flow rate=13; tunnels lead to valves CC, AA
+Valve CC has flow rate=2; tunnels lead to valves DD, BB
+Valve DD has flow rate=20; tunnels lead to valves CC, AA, EE
+Valve EE has flow rate=3; tunnels lead to valves FF, DD
+Valve FF has flow rate=0; tunnels lead to valves EE, GG
+Valve GG has flow rate=0; tunnels lead to valves FF, HH
+Valve HH has flow rate=22; tunnel leads to valve GG
+Valve II has flow rate=0; tunnels lead to valves AA, JJ
+Valve JJ has flow rate=21; tunnel leads to valve II
+";
+
+    #[test]
+    fn example() {
+        let volcano = Aoc16.parse(EXAMPLE).unwrap();
+        assert_eq!(Aoc16.part1(&volcano).unwrap(), Answer::from(1651));
+        assert_eq!(Aoc16.part2(&volcano).unwrap(), Answer::from(1707));
+    }
 }
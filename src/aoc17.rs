@@ -1,11 +1,11 @@
-use core::panic;
 use std::collections::HashMap;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-use std::time::Instant;
+
+use crate::error::{Error, Result};
+use crate::grid::Grid;
+use crate::solver::{Answer, Solver};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
-enum Jet {
+pub enum Jet {
     LEFT,
     RIGHT,
 }
@@ -104,7 +104,7 @@ impl Rock {
                 return true;
             }
         }
-        return false;
+        false
     }
 
     fn translate(&mut self, x: isize, y: isize) {
@@ -142,7 +142,8 @@ impl Rock {
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 struct Map {
-    positions: Vec<Point>,
+    /// the settled rocks, row `y` is `y` above the floor
+    chamber: Grid<bool>,
     top: isize,
     jet_pattern: Vec<Jet>,
     jet_pattern_position: usize,
@@ -151,16 +152,11 @@ struct Map {
 }
 
 impl Map {
-    fn new(jet_pattern: &Vec<Jet>) -> Map {
-        let mut positions = vec![];
-        for x in 0..7 {
-            positions.push(Point { x, y: 0 })
-        }
-
+    fn new(jet_pattern: &[Jet]) -> Map {
         Map {
-            positions,
+            chamber: Grid::new(7, 1, true),
             top: 0,
-            jet_pattern: jet_pattern.clone(),
+            jet_pattern: jet_pattern.to_vec(),
             jet_pattern_position: 0,
             n_rocks: 0,
             toppest: [0, 0, 0, 0, 0, 0, 0],
@@ -199,13 +195,15 @@ impl Map {
         }
     }
 
-    #[allow(dead_code)]
     fn collides_coord(&self, x: isize, y: isize) -> bool {
-        self.positions.iter().any(|p| p.x == x && p.y == y)
+        self.chamber
+            .get(x as usize, y as usize)
+            .copied()
+            .unwrap_or(false)
     }
 
     fn collides(&self, position: &Point) -> bool {
-        self.positions.iter().any(|p| p.eq(position))
+        self.collides_coord(position.x, position.y)
     }
 
     fn collides_rock(&self, rock: &Rock) -> bool {
@@ -214,7 +212,7 @@ impl Map {
                 return true;
             }
         }
-        return false;
+        false
     }
 
     fn spawn(&mut self) {
@@ -224,7 +222,7 @@ impl Map {
             2 => Rock::spawn_l(self.top),
             3 => Rock::spawn_vline(self.top),
             4 => Rock::spawn_block(self.top),
-            x => panic!("which rock!? {} {}", x, self.n_rocks),
+            _ => unreachable!("there are only five rocks"),
         };
 
         let mut round = 0;
@@ -253,7 +251,11 @@ impl Map {
     fn freeze_rock(&mut self, rock: &Rock) {
         for position in rock.positions.iter() {
             let y = position.y + 1;
-            self.positions.push(Point { x: position.x, y });
+            if y as usize >= self.chamber.height() {
+                let missing = y as usize + 1 - self.chamber.height();
+                self.chamber.push_rows(missing, false);
+            }
+            self.chamber[(position.x as usize, y as usize)] = true;
             if self.top < y {
                 self.top = y;
             }
@@ -262,70 +264,36 @@ impl Map {
             }
         }
         self.n_rocks += 1;
-        if self.positions.len() > 200 {
-            let mut min = self.top;
-            for t in self.toppest {
-                if t < min {
-                    min = t;
-                }
-            }
-            let mut new_positions = vec![];
-            for p in self.positions.iter() {
-                if p.y > min - 20 {
-                    new_positions.push(p.clone());
-                }
-            }
-            self.positions = new_positions;
-        }
     }
 
     fn get_top_fingerprint(&self) -> isize {
         let mut fp = 0;
         for x in 0..7 {
-            fp = 10_isize.pow(x) * (self.top as isize - self.toppest[x as usize]);
+            fp = 10_isize.pow(x) * (self.top - self.toppest[x as usize]);
         }
-        return fp;
+        fp
     }
 }
 
-fn aoc17_1(jet_pattern: &Vec<Jet>) {
-    println!("\n\nsolving AOC day 17 part 1");
-
-    let mut map = Map::new(&jet_pattern);
-
-    let current_time = Instant::now();
+fn aoc17_1(jet_pattern: &[Jet]) -> isize {
+    let mut map = Map::new(jet_pattern);
 
     while map.n_rocks < 2022 {
-        if map.n_rocks % 1000 == 0 {
-            println!("rocks {}", map.n_rocks);
-        }
         map.spawn();
     }
 
-    println!(
-        "height: {}  -- time for {} rocks: {:?}",
-        map.top,
-        map.n_rocks,
-        current_time.elapsed()
-    );
+    map.top
 }
 
-fn aoc17_2(jet_pattern: &Vec<Jet>) {
-    println!("\n\nsolving AOC day 17 part 2");
-
-    let mut map = Map::new(&jet_pattern);
+fn aoc17_2(jet_pattern: &[Jet]) -> isize {
+    let mut map = Map::new(jet_pattern);
 
     let mut cache_map = HashMap::new();
 
-    let current_time = Instant::now();
     let nrounds = 1000000000000;
     let mut fast_forward_addition = 0;
 
     while map.n_rocks < nrounds {
-        if map.n_rocks % 1000 == 0 {
-            println!("rocks {}", map.n_rocks);
-        }
-
         if fast_forward_addition == 0 {
             let round = map.n_rocks;
             let current_height = map.top as usize;
@@ -333,7 +301,9 @@ fn aoc17_2(jet_pattern: &Vec<Jet>) {
             let current_jet = map.jet_pattern_position % map.jet_pattern.len();
 
             let cache_key = (current_rock, current_jet, map.get_top_fingerprint());
-            if cache_map.contains_key(&cache_key) {
+            if let std::collections::hash_map::Entry::Vacant(e) = cache_map.entry(cache_key) {
+                e.insert((round, current_height));
+            } else {
                 let (prev_round, prev_height) = cache_map.get(&cache_key).unwrap();
 
                 let height_diff = current_height - prev_height;
@@ -342,41 +312,63 @@ fn aoc17_2(jet_pattern: &Vec<Jet>) {
 
                 fast_forward_addition += repeat * height_diff;
                 map.n_rocks += repeat * round_diff;
-
-                println!(
-                    "can fast-forward round {} to {} (seen the config in round {} already)",
-                    round, map.n_rocks, prev_round
-                );
-            } else {
-                cache_map.insert(cache_key, (round, current_height));
             }
         }
         map.spawn();
     }
-    println!(
-        "height: {}  -- time for {} rocks: {:?}",
-        map.top + fast_forward_addition as isize,
-        map.n_rocks,
-        current_time.elapsed()
-    );
+    map.top + fast_forward_addition as isize
 }
 
-pub fn aoc17() {
-    let reader = BufReader::new(File::open("input-17").unwrap());
-    let mut jet_pattern = vec![];
-    for (_index, line) in reader.lines().enumerate() {
-        let line = line.unwrap();
-        for c in line.chars() {
-            if c == '<' {
-                jet_pattern.push(Jet::LEFT);
-            } else if c == '>' {
-                jet_pattern.push(Jet::RIGHT);
-            } else {
-                panic!("unrecogised jet pattern {} {}", c, line);
+pub struct Aoc17;
+
+impl Solver for Aoc17 {
+    type Input = Vec<Jet>;
+
+    fn parse(&self, input: &str) -> Result<Vec<Jet>> {
+        let mut jet_pattern = vec![];
+        for (index, line) in input.lines().enumerate() {
+            for (column, c) in line.chars().enumerate() {
+                if c == '<' {
+                    jet_pattern.push(Jet::LEFT);
+                } else if c == '>' {
+                    jet_pattern.push(Jet::RIGHT);
+                } else {
+                    return Err(Error::parse(
+                        index + 1,
+                        column + 1,
+                        format!("unrecognised jet {:?}", c),
+                    ));
+                }
             }
         }
+        if jet_pattern.is_empty() {
+            return Err(Error::parse(1, 1, "empty jet pattern"));
+        }
+        Ok(jet_pattern)
     }
 
-    aoc17_1(&jet_pattern);
-    aoc17_2(&jet_pattern);
+    fn part1(&self, jet_pattern: &Vec<Jet>) -> Result<Answer> {
+        Ok(aoc17_1(jet_pattern).into())
+    }
+
+    fn part2(&self, jet_pattern: &Vec<Jet>) -> Result<Answer> {
+        Ok(aoc17_2(jet_pattern).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = ">>><<><>><<<>><>>><<<>>><<<><<<>><>><<>>\n";
+
+    #[test]
+    fn example() {
+        let jet_pattern = Aoc17.parse(EXAMPLE).unwrap();
+        assert_eq!(Aoc17.part1(&jet_pattern).unwrap(), Answer::from(3068));
+        assert_eq!(
+            Aoc17.part2(&jet_pattern).unwrap(),
+            Answer::from(1514285714288_i64)
+        );
+    }
 }
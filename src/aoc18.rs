@@ -1,10 +1,9 @@
 use min_max::*;
-// use parse_int::parse;
-// use substring::Substring;
-use parse_int::parse;
 use std::collections::HashSet;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+
+use crate::error::{self, Error, Result};
+use crate::solver::{Answer, Solver};
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 struct Cube {
     x: isize,
@@ -13,14 +12,25 @@ struct Cube {
 }
 
 impl Cube {
-    fn new(line: &str) -> Cube {
-        let coord = line.split(",").collect::<Vec<&str>>();
-        assert!(coord.len() == 3);
-        Cube {
-            x: parse::<isize>(coord.get(0).unwrap()).unwrap(),
-            y: parse::<isize>(coord.get(1).unwrap()).unwrap(),
-            z: parse::<isize>(coord.get(2).unwrap()).unwrap(),
+    fn new(line: &str, index: usize) -> Result<Cube> {
+        let coord = line.split(',').collect::<Vec<&str>>();
+        if coord.len() != 3 {
+            return Err(Error::line(
+                index,
+                format!("expected 3 coordinates, found {}", coord.len()),
+            ));
+        }
+        let mut offset = 0;
+        let mut values = [0; 3];
+        for (value, c) in values.iter_mut().zip(coord.iter()) {
+            *value = error::number::<isize>(c, index, offset)?;
+            offset += c.len() + 1;
         }
+        Ok(Cube {
+            x: values[0],
+            y: values[1],
+            z: values[2],
+        })
     }
 
     fn fingerprint(&self) -> isize {
@@ -58,9 +68,7 @@ impl BoundingBox {
     }
 }
 
-fn aoc18_1(cubes: &Vec<Cube>, coords: &HashSet<isize>) {
-    println!("solving AOC day 18 part 1");
-
+fn aoc18_1(cubes: &[Cube], coords: &HashSet<isize>) -> usize {
     let mut score = 0;
     for cube in cubes.iter() {
         let neighbors = cube.get_neighbor_fingerprints();
@@ -70,12 +78,10 @@ fn aoc18_1(cubes: &Vec<Cube>, coords: &HashSet<isize>) {
             }
         }
     }
-    println!("cubes exposed to air: {}", score);
+    score
 }
 
-fn aoc18_2(cubes: &Vec<Cube>, cube_coords: &HashSet<isize>, bounding_box: &BoundingBox) {
-    println!("solving AOC day 18 part 2");
-
+fn aoc18_2(cubes: &[Cube], cube_coords: &HashSet<isize>, bounding_box: &BoundingBox) -> usize {
     let mut space_coords = HashSet::new();
 
     let mut change_smt = true;
@@ -115,24 +121,78 @@ fn aoc18_2(cubes: &Vec<Cube>, cube_coords: &HashSet<isize>, bounding_box: &Bound
             }
         }
     }
-    println!("cubes exposed to fresh air: {}", score);
+    score
+}
+
+pub struct Droplet {
+    cubes: Vec<Cube>,
+    coords: HashSet<isize>,
+    bounding_box: BoundingBox,
+}
+
+pub struct Aoc18;
+
+impl Solver for Aoc18 {
+    type Input = Droplet;
+
+    fn parse(&self, input: &str) -> Result<Droplet> {
+        let mut cubes: Vec<Cube> = vec![];
+        let mut coords = HashSet::new();
+        let mut bounding_box = BoundingBox::new();
+
+        for (index, line) in input.lines().enumerate() {
+            let cube = Cube::new(line, index)?;
+            coords.insert(cube.fingerprint());
+            bounding_box.update(&cube);
+            cubes.push(cube);
+        }
+
+        Ok(Droplet {
+            cubes,
+            coords,
+            bounding_box,
+        })
+    }
+
+    fn part1(&self, droplet: &Droplet) -> Result<Answer> {
+        Ok(aoc18_1(&droplet.cubes, &droplet.coords).into())
+    }
+
+    fn part2(&self, droplet: &Droplet) -> Result<Answer> {
+        Ok(aoc18_2(&droplet.cubes, &droplet.coords, &droplet.bounding_box).into())
+    }
 }
 
-pub fn aoc18() {
-    let reader = BufReader::new(File::open("input-18").unwrap());
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    let mut cubes: Vec<Cube> = vec![];
-    let mut coords = HashSet::new();
-    let mut bounding_box = BoundingBox::new();
+    const EXAMPLE: &str = "\
+2,2,2
+1,2,2
+3,2,2
+2,1,2
+2,3,2
+2,2,1
+2,2,3
+2,2,4
+2,2,6
+1,2,5
+3,2,5
+2,1,5
+2,3,5
+";
 
-    for (_index, line) in reader.lines().enumerate() {
-        let line = line.unwrap();
-        let cube = Cube::new(&line);
-        coords.insert(cube.fingerprint());
-        bounding_box.update(&cube);
-        cubes.push(cube);
+    #[test]
+    fn example() {
+        let droplet = Aoc18.parse(EXAMPLE).unwrap();
+        assert_eq!(Aoc18.part1(&droplet).unwrap(), Answer::from(64));
+        assert_eq!(Aoc18.part2(&droplet).unwrap(), Answer::from(58));
     }
 
-    aoc18_1(&cubes, &coords);
-    aoc18_2(&cubes, &coords, &bounding_box);
+    #[test]
+    fn two_cubes() {
+        let droplet = Aoc18.parse("1,1,1\n2,1,1\n").unwrap();
+        assert_eq!(Aoc18.part1(&droplet).unwrap(), Answer::from(10));
+    }
 }
@@ -1,15 +1,15 @@
-use parse_int::parse;
 use itertools::Itertools;
 use overload::overload;
 use rayon::prelude::*;
 use regex::Regex;
 use std::cmp::Ordering;
 use std::fmt::Debug;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
 use std::ops;
-use std::thread::JoinHandle;
 use std::thread;
+use std::thread::JoinHandle;
+
+use crate::error::{self, Error, Result};
+use crate::solver::{Answer, Solver};
 
 #[derive(Clone, Hash, Eq, PartialEq)]
 struct InventoryState {
@@ -24,7 +24,6 @@ struct InventoryState {
 }
 
 impl InventoryState {
-
     fn cmp(&self, other: &Self) -> Ordering {
         usize::cmp(&self.geode, &other.geode)
             .then(usize::cmp(&self.geode_robots, &other.geode_robots))
@@ -58,24 +57,23 @@ impl InventoryState {
     }
 }
 
-
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 struct Cost {
     ore: usize,
     clay: usize,
     obsidian: usize,
 }
-overload!((a: Cost) * (b: Cost) -> Cost { Cost { 
+overload!((a: Cost) * (b: Cost) -> Cost { Cost {
                 ore: a.ore * b.ore,
                 clay: a.clay * b.clay,
                 obsidian: a.obsidian * b.obsidian, } });
-overload!((a: Cost) + (b: Cost) -> Cost { Cost { 
+overload!((a: Cost) + (b: Cost) -> Cost { Cost {
                 ore: a.ore + b.ore,
                 clay: a.clay + b.clay,
                 obsidian: a.obsidian + b.obsidian, } });
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-struct BluePrint {
+pub struct BluePrint {
     id: usize,
     ore_cost: Cost,
     clay_cost: Cost,
@@ -85,9 +83,9 @@ struct BluePrint {
 }
 
 impl BluePrint {
-    fn run(&self, max_time: usize) -> usize {
-        let mut states = vec![];
-        states.push(InventoryState {
+    /// the maximum number of geodes that can be opened within `max_time` minutes
+    pub fn run(&self, max_time: usize) -> usize {
+        let mut states = vec![InventoryState {
             ore_robots: 1,
             clay_robots: 0,
             obsidian_robots: 0,
@@ -96,7 +94,7 @@ impl BluePrint {
             clay: 0,
             obsidian: 0,
             geode: 0,
-        });
+        }];
         for _ in 0..max_time {
             let new_states: Vec<InventoryState> = states
                 .iter_mut()
@@ -116,109 +114,133 @@ impl BluePrint {
                         n.push(state.buy(1, 0, 0, 0, &self.ore_cost));
                     }
 
-                    n.push(state.buy(0,0,0,0, &self.empty_cost));
+                    n.push(state.buy(0, 0, 0, 0, &self.empty_cost));
                     n
                 })
                 .collect();
 
             states = new_states
                 .iter()
-                .sorted_by(|a, b| InventoryState::cmp(&b, &a))
+                .sorted_by(|a, b| InventoryState::cmp(b, a))
                 .take(123456)
-                .map(|a| a.clone())
+                .cloned()
                 .collect();
         }
-        let best_solution = states.iter().map(|s| s.geode).max();
-
-        return best_solution.unwrap();
+        states.iter().map(|s| s.geode).max().unwrap_or(0)
     }
 }
 
-fn aoc19_1(blue_prints: &Vec<BluePrint>) {
-    println!("solving AOC day 19 part 1");
-
+fn aoc19_1(blue_prints: &[BluePrint]) -> usize {
     let sum: usize = (0..blue_prints.len())
         .into_par_iter()
         .map(|bp| {
             let blue_print = blue_prints.get(bp).unwrap().clone();
-            let result = blue_print.run(24);
-            println!(
-                "found solution for bp {} -> {} = {}",
-                bp,
-                result,
-                result * (bp + 1)
-            );
-            return result * (bp + 1);
+            blue_print.run(24) * (bp + 1)
         })
         .sum();
 
-    println!("sum: {}", sum);
+    sum
 }
 
-fn aoc19_2(blue_prints: &Vec<BluePrint>) {
-    println!("solving AOC day 19 part 2");
-
+fn aoc19_2(blue_prints: &[BluePrint]) -> usize {
     let mut handles: Vec<JoinHandle<usize>> = vec![];
 
-    for bp in 0..3 {
-        let blue_print = blue_prints.get(bp).unwrap().clone();
-        handles.push(thread::spawn(move || {
-            let result = blue_print.run(32);
-            println!(
-                "found solution for bp {} -> {} = {}",
-                bp,
-                result,
-                result * (bp + 1)
-            );
-            return result;
-        }));
+    for blue_print in blue_prints.iter().take(3) {
+        let blue_print = blue_print.clone();
+        handles.push(thread::spawn(move || blue_print.run(32)));
     }
 
     let mut product = 1;
-    while handles.len() > 0 {
+    while !handles.is_empty() {
         let handle = handles.remove(0);
         let result = handle.join().unwrap();
         product *= result;
     }
-    println!("product: {}", product);
+    product
 }
 
-pub fn aoc19() {
-    let reader = BufReader::new(File::open("input-19").unwrap());
-    let re = Regex::new(r"^Blueprint (\d+): Each ore robot costs (\d+) ore. Each clay robot costs (\d+) ore. Each obsidian robot costs (\d+) ore and (\d+) clay. Each geode robot costs (\d+) ore and (\d+) obsidian.$")
-        .unwrap();
-
-    let mut blue_prints = vec![];
-    for line in reader.lines() {
-        let line = line.unwrap();
-        for cap in re.captures_iter(&line) {
+pub struct Aoc19;
+
+impl Solver for Aoc19 {
+    type Input = Vec<BluePrint>;
+
+    fn parse(&self, input: &str) -> Result<Vec<BluePrint>> {
+        let re = Regex::new(r"^Blueprint (\d+): Each ore robot costs (\d+) ore. Each clay robot costs (\d+) ore. Each obsidian robot costs (\d+) ore and (\d+) clay. Each geode robot costs (\d+) ore and (\d+) obsidian.$")
+            .unwrap();
+
+        let mut blue_prints = vec![];
+        for (index, line) in input.lines().enumerate() {
+            let cap = re
+                .captures(line)
+                .ok_or_else(|| Error::line(index, format!("invalid blueprint {:?}", line)))?;
+            let mut numbers = vec![];
+            for group in 1..=7 {
+                let m = cap.get(group).unwrap();
+                numbers.push(error::number::<usize>(m.as_str(), index, m.start())?);
+            }
             blue_prints.push(BluePrint {
-                id: parse::<usize>(&cap[1]).unwrap(),
+                id: numbers[0],
                 ore_cost: Cost {
-                    ore: parse::<usize>(&cap[2]).unwrap(),
+                    ore: numbers[1],
                     clay: 0,
                     obsidian: 0,
                 },
                 clay_cost: Cost {
-                    ore: parse::<usize>(&cap[3]).unwrap(),
+                    ore: numbers[2],
                     clay: 0,
                     obsidian: 0,
                 },
                 obsidian_cost: Cost {
-                    ore: parse::<usize>(&cap[4]).unwrap(),
-                    clay: parse::<usize>(&cap[5]).unwrap(),
+                    ore: numbers[3],
+                    clay: numbers[4],
                     obsidian: 0,
                 },
                 geode_cost: Cost {
-                    ore: parse::<usize>(&cap[6]).unwrap(),
+                    ore: numbers[5],
+                    clay: 0,
+                    obsidian: numbers[6],
+                },
+                empty_cost: Cost {
+                    ore: 0,
                     clay: 0,
-                    obsidian: parse::<usize>(&cap[7]).unwrap(),
+                    obsidian: 0,
                 },
-                empty_cost: Cost{ ore: 0, clay: 0, obsidian: 0 }
             });
         }
+        Ok(blue_prints)
+    }
+
+    fn part1(&self, blue_prints: &Vec<BluePrint>) -> Result<Answer> {
+        Ok(aoc19_1(blue_prints).into())
+    }
+
+    fn part2(&self, blue_prints: &Vec<BluePrint>) -> Result<Answer> {
+        Ok(aoc19_2(blue_prints).into())
     }
+}
 
-    aoc19_1(&blue_prints);
-    aoc19_2(&blue_prints);
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+Blueprint 1: Each ore robot costs 4 ore. Each clay robot costs 2 ore. Each obsidian robot costs 3 ore and 14 clay. Each geode robot costs 2 ore and 7 obsidian.
+Blueprint 2: Each ore robot costs 2 ore. Each clay robot costs 3 ore. Each obsidian robot costs 3 ore and 8 clay. Each geode robot costs 3 ore and 12 obsidian.
+";
+
+    #[test]
+    fn example() {
+        let blue_prints = Aoc19.parse(EXAMPLE).unwrap();
+        assert_eq!(blue_prints[0].run(24), 9);
+        assert_eq!(blue_prints[1].run(24), 12);
+        assert_eq!(Aoc19.part1(&blue_prints).unwrap(), Answer::from(33));
+    }
+
+    #[test]
+    #[ignore = "takes a while in debug builds"]
+    fn example_32_minutes() {
+        let blue_prints = Aoc19.parse(EXAMPLE).unwrap();
+        assert_eq!(blue_prints[0].run(32), 56);
+        assert_eq!(blue_prints[1].run(32), 62);
+    }
 }
@@ -1,10 +1,8 @@
-use parse_int::parse;
-use std::fs::File;
-use std::io::{self, Write};
-use std::io::{BufRead, BufReader};
+use crate::error::{self, Error, Result};
+use crate::solver::{Answer, Solver};
 
 #[derive(Debug, Clone)]
-struct GrooveCoordinate {
+pub struct GrooveCoordinate {
     initial_index: usize,
     value: isize,
 }
@@ -25,10 +23,6 @@ struct GrooveCoordinates {
 impl GrooveCoordinates {
     fn mix(&mut self) {
         for i in 0..self.coordinate_list.len() {
-            if i % 50 == 0 {
-                print!(".");
-                io::stdout().flush().unwrap();
-            }
             self.mix1(i);
         }
     }
@@ -48,74 +42,113 @@ impl GrooveCoordinates {
         self.coordinate_list.insert(target_index, element);
     }
 
-    fn find_groove(&self) {
+    fn find_groove(&self) -> Result<isize> {
         let element_index = self
             .coordinate_list
             .iter()
             .enumerate()
             .find(|a| a.1.value == 0)
             .map(|a| a.0)
-            .unwrap();
+            .ok_or_else(|| Error::no_solution("there is no coordinate 0"))?;
         let k1 = (element_index + 1000) % self.coordinate_list.len();
         let k2 = (element_index + 2000) % self.coordinate_list.len();
         let k3 = (element_index + 3000) % self.coordinate_list.len();
-        println!(
-            "found 0 at {} -- 1k: {} -- 2k: {} -- 3k: {} -- sum: {}",
-            element_index,
-            self.coordinate_list[k1].value,
-            self.coordinate_list[k2].value,
-            self.coordinate_list[k3].value,
-            self.coordinate_list[k1].value
-                + self.coordinate_list[k2].value
-                + self.coordinate_list[k3].value
-        );
+        Ok(self.coordinate_list[k1].value
+            + self.coordinate_list[k2].value
+            + self.coordinate_list[k3].value)
     }
 }
 
-fn aoc20_1(input: &Vec<GrooveCoordinate>) {
-    println!("solving AOC day 20 part 1");
-
+fn aoc20_1(input: &[GrooveCoordinate]) -> Result<isize> {
     let mut list = GrooveCoordinates {
-        coordinate_list: input.clone(),
+        coordinate_list: input.to_vec(),
     };
     list.mix();
-    println!("");
-    list.find_groove();
+    list.find_groove()
 }
 
-fn aoc20_2(input: &Vec<GrooveCoordinate>) {
-    println!("\nsolving AOC day 20 part 2");
-
+fn aoc20_2(input: &[GrooveCoordinate]) -> Result<isize> {
     let decryption_key = 811589153;
     let mut list = GrooveCoordinates {
         coordinate_list: input
             .iter()
             .map(|e| {
                 let mut e = e.clone();
-                e.value = e.value * decryption_key;
+                e.value *= decryption_key;
                 e
             })
             .collect(),
     };
 
-    for i in 0..10 {
-        print!("round {} ", i);
+    for _ in 0..10 {
         list.mix();
-        println!("");
     }
 
-    list.find_groove();
+    list.find_groove()
+}
+
+pub struct Aoc20;
+
+impl Solver for Aoc20 {
+    type Input = Vec<GrooveCoordinate>;
+
+    fn parse(&self, input: &str) -> Result<Vec<GrooveCoordinate>> {
+        let coordinates = input
+            .lines()
+            .enumerate()
+            .map(|(index, line)| {
+                Ok(GrooveCoordinate::new(
+                    index,
+                    error::number::<isize>(line, index, 0)?,
+                ))
+            })
+            .collect::<Result<Vec<GrooveCoordinate>>>()?;
+        // a number moves around the others, so there have to be others
+        if coordinates.len() < 2 {
+            return Err(Error::line(
+                coordinates.len(),
+                "mixing needs at least two numbers",
+            ));
+        }
+        Ok(coordinates)
+    }
+
+    fn part1(&self, input: &Vec<GrooveCoordinate>) -> Result<Answer> {
+        Ok(aoc20_1(input)?.into())
+    }
+
+    fn part2(&self, input: &Vec<GrooveCoordinate>) -> Result<Answer> {
+        Ok(aoc20_2(input)?.into())
+    }
 }
 
-pub fn aoc20() {
-    let reader = BufReader::new(File::open("input-20").unwrap());
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+1
+2
+-3
+3
+-2
+0
+4
+";
 
-    let input = reader
-        .lines()
-        .enumerate()
-        .map(|(index, line)| GrooveCoordinate::new(index, parse::<isize>(&line.unwrap()).unwrap()))
-        .collect();
+    #[test]
+    fn example() {
+        let input = Aoc20.parse(EXAMPLE).unwrap();
+        assert_eq!(Aoc20.part1(&input).unwrap(), Answer::from(3));
+        assert_eq!(Aoc20.part2(&input).unwrap(), Answer::from(1623178306));
+    }
 
-    aoc20_1(&input);
-    aoc20_2(&input);
+    #[test]
+    fn too_few_numbers() {
+        assert!(matches!(
+            Aoc20.parse("0\n"),
+            Err(Error::Parse { line: 2, .. })
+        ));
+        assert!(Aoc20.parse("").is_err());
+    }
 }
@@ -1,11 +1,13 @@
 use parse_int::parse;
 use regex::Regex;
 use std::collections::HashMap;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
 use substring::Substring;
 
-struct Monkeys {
+use crate::error::{Error, Result};
+use crate::solver::{Answer, Solver};
+
+#[derive(Clone)]
+pub struct Monkeys {
     map: HashMap<String, String>,
     calc: String,
 }
@@ -18,14 +20,17 @@ impl Monkeys {
         }
     }
 
-    fn add(&mut self, line: &str) {
-        let re = Regex::new(r"^([a-zA-Z]{4}): (.*)$").unwrap();
-        for cap in re.captures_iter(&line) {
-            self.map.insert(cap[1].to_string(), cap[2].to_string());
-        }
+    fn add(&mut self, line: &str, index: usize) -> Result<()> {
+        let re = Regex::new(r"^([a-zA-Z]{4}): (\d+|[a-zA-Z]{4} [+\-/*] [a-zA-Z]{4})$").unwrap();
+        let cap = re
+            .captures(line)
+            .ok_or_else(|| Error::line(index, format!("invalid monkey job {:?}", line)))?;
+        self.map.insert(cap[1].to_string(), cap[2].to_string());
+        Ok(())
     }
 
-    fn calc(&mut self) -> String {
+    /// evaluates all parts of the resolved calculation that do not involve the human
+    pub fn calc(&mut self) -> String {
         let has_calc = Regex::new(r"\((\d+) ([+-/*]) (\d+)\)").unwrap();
 
         let mut replaced = true;
@@ -43,7 +48,7 @@ impl Monkeys {
                     "-" => first - second,
                     "*" => first * second,
                     "/" => first / second,
-                    _ => panic!("do not understand operator {}", operator),
+                    _ => unreachable!("operators are validated while parsing"),
                 };
                 new_calc = new_calc.replace(&cap[0], &replacement.to_string());
                 replaced = true;
@@ -53,8 +58,13 @@ impl Monkeys {
         self.calc.clone()
     }
 
-    fn resolve(&mut self, include_human: bool) -> String {
-        self.calc = self.map.get("root").unwrap().clone();
+    /// replaces the monkeys in the calculation of root by their jobs
+    pub fn resolve(&mut self, include_human: bool) -> Result<String> {
+        self.calc = self
+            .map
+            .get("root")
+            .ok_or_else(|| Error::no_solution("there is no root monkey"))?
+            .clone();
         let has_vars = Regex::new(r"([a-zA-Z]{4})").unwrap();
 
         let mut replaced = true;
@@ -65,21 +75,24 @@ impl Monkeys {
             for cap in has_vars.captures_iter(&self.calc) {
                 let var = &cap[1];
                 if include_human || var != "humn" {
-                    let replacement = self.map.get(var).unwrap();
-                    if has_vars.is_match(&replacement) {
+                    let replacement = self
+                        .map
+                        .get(var)
+                        .ok_or_else(|| Error::no_solution(format!("there is no monkey {}", var)))?;
+                    if has_vars.is_match(replacement) {
                         new_calc = new_calc.replace(var, &format!("({})", replacement));
                     } else {
-                        new_calc = new_calc.replace(var, &replacement);
+                        new_calc = new_calc.replace(var, replacement);
                     }
                     replaced = true;
                 }
             }
             self.calc = new_calc;
         }
-        self.calc.clone()
+        Ok(self.calc.clone())
     }
 
-    fn calc_human_value(number: isize, complex_human: &str) -> (isize, String) {
+    fn calc_human_value(number: isize, complex_human: &str) -> Result<(isize, String)> {
         let mut complex_human = complex_human.to_string();
 
         if complex_human.starts_with("(") && complex_human.ends_with(")") {
@@ -89,8 +102,7 @@ impl Monkeys {
         }
 
         if complex_human == "humn" {
-            println!("found humn to be {}", number);
-            return (0, "".to_string());
+            return Ok((number, "".to_string()));
         }
 
         let mut calc_parts =
@@ -104,9 +116,11 @@ impl Monkeys {
                     .unwrap();
         }
 
-        let cap = calc_parts.captures(&complex_human).unwrap(); //.a
+        let cap = calc_parts.captures(&complex_human).ok_or_else(|| {
+            Error::no_solution(format!("cannot isolate humn in {}", complex_human))
+        })?;
 
-        let cur_number = parse::<isize>(&cap.name("number").unwrap().as_str()).unwrap();
+        let cur_number = parse::<isize>(cap.name("number").unwrap().as_str()).unwrap();
         let complex = cap.name("complex").unwrap().as_str();
         let operator = cap.name("operator").unwrap().as_str();
 
@@ -127,13 +141,14 @@ impl Monkeys {
                     cur_number / number
                 }
             }
-            _ => panic!("do not understand operator {}", operator),
+            _ => unreachable!("operators are validated while parsing"),
         };
 
-        return (new_number, complex.to_string());
+        Ok((new_number, complex.to_string()))
     }
 
-    fn calc_human(&mut self) {
+    /// the number the human needs to yell so that both sides of root match
+    pub fn calc_human(&mut self) -> Result<isize> {
         let mut calc_parts =
             Regex::new(r"^\((?P<complex>.*)\) (?P<operator>[+-/*]) (?P<number>\d+)$").unwrap();
 
@@ -142,53 +157,104 @@ impl Monkeys {
                 Regex::new(r"^(?P<number>\d+) (?P<operator>[+-/*]) \((?P<complex>.*)\)$").unwrap();
         }
 
-        let cap = calc_parts.captures(&self.calc).unwrap(); //.and_then(|cap| {
+        let cap = calc_parts
+            .captures(&self.calc)
+            .ok_or_else(|| Error::no_solution(format!("cannot isolate humn in {}", self.calc)))?;
 
         let mut complex: String = cap.name("complex").unwrap().as_str().to_string();
-        let mut number = parse::<isize>(&cap.name("number").unwrap().as_str()).unwrap();
+        let mut number = parse::<isize>(cap.name("number").unwrap().as_str()).unwrap();
 
-        while complex.len() > 0 {
-            let (result, new_complex) = Monkeys::calc_human_value(number, &complex);
+        while !complex.is_empty() {
+            let (result, new_complex) = Monkeys::calc_human_value(number, &complex)?;
             complex = new_complex.to_string();
             number = result;
         }
+        Ok(number)
     }
 }
 
-fn aoc21_1(monkeys: &mut Monkeys) {
-    println!("solving AOC day 21 part 1");
-    monkeys.resolve(true);
+fn aoc21_1(monkeys: &mut Monkeys) -> Result<isize> {
+    monkeys.resolve(true)?;
     let calc_result = monkeys.calc();
 
     if calc_result.contains(" + ") {
         let parts = calc_result.split(" + ");
         let mut sum = 0;
         for part in parts {
-            sum += parse::<isize>(part).unwrap();
+            sum += parse::<isize>(part)
+                .map_err(|_| Error::no_solution(format!("cannot compute {} of root", part)))?;
         }
-        println!("{} = {}", calc_result, sum);
+        Ok(sum)
     } else {
-        panic!("unexpeced calc result {}", calc_result);
+        Err(Error::no_solution(format!(
+            "unexpected calc result {}",
+            calc_result
+        )))
     }
 }
 
-fn aoc21_2(monkeys: &mut Monkeys) {
-    println!("\nsolving AOC day 21 part 2");
-    monkeys.resolve(false);
+fn aoc21_2(monkeys: &mut Monkeys) -> Result<isize> {
+    monkeys.resolve(false)?;
     monkeys.calc();
-    monkeys.calc_human();
+    monkeys.calc_human()
+}
+
+pub struct Aoc21;
+
+impl Solver for Aoc21 {
+    type Input = Monkeys;
+
+    fn parse(&self, input: &str) -> Result<Monkeys> {
+        let mut monkeys = Monkeys::new();
+        for (index, line) in input.lines().enumerate() {
+            monkeys.add(line, index)?;
+        }
+        Ok(monkeys)
+    }
+
+    fn part1(&self, monkeys: &Monkeys) -> Result<Answer> {
+        Ok(aoc21_1(&mut monkeys.clone())?.into())
+    }
+
+    fn part2(&self, monkeys: &Monkeys) -> Result<Answer> {
+        Ok(aoc21_2(&mut monkeys.clone())?.into())
+    }
 }
 
-pub fn aoc21() {
-    let reader = BufReader::new(File::open("input-21").unwrap());
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    let mut monkeys = Monkeys::new();
+    const EXAMPLE: &str = "\
+root: pppw + sjmn
+dbpl: 5
+cczh: sllz + lgvd
+zczc: 2
+ptdq: humn - dvpt
+dvpt: 3
+lfqf: 4
+humn: 5
+ljgn: 2
+sjmn: drzm * dbpl
+sllz: 4
+pppw: cczh / lfqf
+lgvd: ljgn * ptdq
+drzm: hmdt - zczc
+hmdt: 32
+";
 
-    for (_index, line) in reader.lines().enumerate() {
-        let line = line.unwrap();
-        monkeys.add(&line);
+    #[test]
+    fn example() {
+        let monkeys = Aoc21.parse(EXAMPLE).unwrap();
+        assert_eq!(Aoc21.part1(&monkeys).unwrap(), Answer::from(152));
+        assert_eq!(Aoc21.part2(&monkeys).unwrap(), Answer::from(301));
     }
 
-    aoc21_1(&mut monkeys);
-    aoc21_2(&mut monkeys);
+    #[test]
+    fn calc_human() {
+        let mut monkeys = Aoc21.parse(EXAMPLE).unwrap();
+        monkeys.resolve(false).unwrap();
+        monkeys.calc();
+        assert_eq!(monkeys.calc_human().unwrap(), 301);
+    }
 }
@@ -6,8 +6,10 @@ use nom::IResult;
 use nom::*;
 use std::collections::HashMap;
 use std::fmt;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+
+use crate::error::{Error, Result};
+use crate::grid::Grid;
+use crate::solver::{Answer, Solver};
 
 #[derive(Debug, Clone, Eq, PartialEq, Copy)]
 struct Position {
@@ -16,7 +18,7 @@ struct Position {
 }
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
-enum Direction {
+pub enum Direction {
     LEFT,
     RIGHT,
     UP,
@@ -42,7 +44,7 @@ enum Field {
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
-enum Go {
+pub enum Go {
     STEPS(u8),
     TURN(Direction),
 }
@@ -63,106 +65,98 @@ impl fmt::Display for Field {
 }
 
 #[derive(Debug, Clone)]
-struct Map {
-    map: Vec<Vec<Field>>,
-    max_len: usize,
+pub struct Map {
+    map: Grid<Field>,
     current_position: Option<Position>,
     current_direction: Direction,
     position_map: HashMap<(i16, i16, Direction), (u16, u16, Direction)>,
 }
 impl Map {
-    fn new() -> Map {
-        Map {
-            map: vec![],
-            current_position: None,
+    /// parses the map part of the notes, rows may be of different lengths
+    fn new(input: &str) -> Result<Map> {
+        let map = Grid::parse_ragged(input, Field::EMPTY, |c| match c {
+            ' ' => Some(Field::EMPTY),
+            '.' => Some(Field::OPEN(None)),
+            '#' => Some(Field::SOLID),
+            _ => None,
+        })?;
+        let current_position = map
+            .find(|field| matches!(field, Field::OPEN(_)))
+            .map(|(x, y)| Position {
+                x: x as u16,
+                y: y as u16,
+            });
+        Ok(Map {
+            map,
+            current_position,
             current_direction: Direction::RIGHT,
-            max_len: 0,
             position_map: HashMap::new(),
-        }
+        })
     }
 
-    fn add_line(&mut self, line: &str) {
-        let row: Vec<Field> = line
-            .chars()
-            .enumerate()
-            .map(|(i, c)| match c {
-                ' ' => Field::EMPTY,
-                '.' => {
-                    if self.current_position == None {
-                        self.current_position = Some(Position {
-                            x: i as u16,
-                            y: self.map.len() as u16,
-                        });
-                    }
-                    Field::OPEN(None)
-                }
-                '#' => Field::SOLID,
-                _ => panic!("don't understand field value {}", c),
-            })
-            .collect();
-        if row.len() > self.max_len {
-            self.max_len = row.len()
-        }
-        self.map.push(row);
-    }
-
-    fn gen_positionmap_part1(&mut self) {
+    /// wraps around to the other end of the row or column, whatever the shape of the map
+    pub fn gen_positionmap_part1(&mut self) {
         use Direction::*;
         self.position_map.clear();
 
-        let s = (self.max_len / 3) as u16;
-        assert_eq!(self.map.len() as u16, s * 4);
-
-        for y in 0..s {
-            self.position_map
-                .insert((s as i16 - 1, y as i16, LEFT), (s * 3 - 1, y, LEFT));
-            self.position_map
-                .insert((s as i16 * 3, y as i16, RIGHT), (s, y, RIGHT));
-        }
-        for y in s..2 * s {
-            self.position_map
-                .insert((s as i16 - 1, y as i16, LEFT), (s * 2 - 1, y, LEFT));
-            self.position_map
-                .insert((s as i16 * 2, y as i16, RIGHT), (s, y, RIGHT));
-        }
-        for y in 2 * s..3 * s {
-            self.position_map
-                .insert((-1, y as i16, LEFT), (s * 2 - 1, y, LEFT));
-            self.position_map
-                .insert((s as i16 * 2, y as i16, RIGHT), (0, y, RIGHT));
-        }
-        for y in 3 * s..4 * s {
-            self.position_map
-                .insert((-1, y as i16, LEFT), (s - 1, y, LEFT));
-            self.position_map
-                .insert((s as i16, y as i16, RIGHT), (0, y, RIGHT));
-        }
+        let on_map = |x: usize, y: usize| !matches!(self.map.get(x, y), None | Some(Field::EMPTY));
 
-        for x in 0..s {
-            self.position_map
-                .insert((x as i16, s as i16 * 2 - 1, UP), (x, s * 4 - 1, UP));
-            self.position_map
-                .insert((x as i16, s as i16 * 4, DOWN), (x, s * 2, DOWN));
-        }
-        for x in s..2 * s {
-            self.position_map
-                .insert((x as i16, -1, UP), (x, s * 3 - 1, UP));
-            self.position_map
-                .insert((x as i16, s as i16 * 3, DOWN), (x, 0, DOWN));
+        for y in 0..self.map.height() {
+            let tiles: Vec<usize> = (0..self.map.width()).filter(|x| on_map(*x, y)).collect();
+            if let (Some(first), Some(last)) = (tiles.first(), tiles.last()) {
+                let (first, last, y) = (*first as u16, *last as u16, y as u16);
+                self.position_map
+                    .insert((first as i16 - 1, y as i16, LEFT), (last, y, LEFT));
+                self.position_map
+                    .insert((last as i16 + 1, y as i16, RIGHT), (first, y, RIGHT));
+            }
         }
-        for x in 2 * s..3 * s {
-            self.position_map.insert((x as i16, -1, UP), (x, s - 1, UP));
-            self.position_map
-                .insert((x as i16, s as i16, DOWN), (x, 0, DOWN));
+        for x in 0..self.map.width() {
+            let tiles: Vec<usize> = (0..self.map.height()).filter(|y| on_map(x, *y)).collect();
+            if let (Some(first), Some(last)) = (tiles.first(), tiles.last()) {
+                let (first, last, x) = (*first as u16, *last as u16, x as u16);
+                self.position_map
+                    .insert((x as i16, first as i16 - 1, UP), (x, last, UP));
+                self.position_map
+                    .insert((x as i16, last as i16 + 1, DOWN), (x, first, DOWN));
+            }
         }
     }
 
-    fn gen_positionmap_part2(&mut self) {
+    /// Folds the map into a cube, only for the layout of our input.
+    ///
+    /// The faces have to be laid out in a grid of 3 by 4 faces like this:
+    ///
+    /// ```text
+    ///  ##
+    ///  #
+    /// ##
+    /// #
+    /// ```
+    pub fn gen_positionmap_part2(&mut self) -> Result<()> {
         use Direction::*;
         self.position_map.clear();
 
-        let s = (self.max_len / 3) as u16;
-        assert_eq!(self.map.len() as u16, s * 4);
+        const FACES: [&str; 4] = [".##", ".#.", "##.", "#.."];
+        let s = self.map.width() / 3;
+        let supported = s > 0
+            && self.map.width() == s * 3
+            && self.map.height() == s * 4
+            && FACES.iter().enumerate().all(|(fy, row)| {
+                row.chars().enumerate().all(|(fx, face)| {
+                    let on_map = !matches!(self.map.get(fx * s, fy * s), None | Some(Field::EMPTY));
+                    on_map == (face == '#')
+                })
+            });
+        if !supported {
+            return Err(Error::no_solution(format!(
+                "can only fold a cube laid out as {:?}, the map is {}x{}",
+                FACES,
+                self.map.width(),
+                self.map.height()
+            )));
+        }
+        let s = s as u16;
 
         for y in 0..s {
             self.position_map
@@ -220,14 +214,7 @@ impl Map {
             self.position_map
                 .insert((x as i16, s as i16, DOWN), (s * 2 - 1, s + field_pos, LEFT));
         }
-    }
-
-    fn fill_up(&mut self) {
-        for row in 0..self.map.len() {
-            while self.map[row].len() < self.max_len {
-                self.map[row].push(Field::EMPTY);
-            }
-        }
+        Ok(())
     }
 
     fn next_position(
@@ -246,95 +233,94 @@ impl Map {
             let p = self.position_map.get(&key).unwrap();
             return (Position { x: p.0, y: p.1 }, p.2);
         }
-        return (
+        (
             Position {
                 x: (position.x as i16 + change_x) as u16,
                 y: (position.y as i16 + change_y) as u16,
             },
             direction,
-        );
+        )
     }
 
     fn go_next_position(
         &mut self,
         position: &Position,
         direction: &Direction,
-    ) -> Option<(Position, Direction)> {
+    ) -> Result<Option<(Position, Direction)>> {
         use Direction::*;
         use Field::*;
         match direction {
             LEFT => {
                 let (quest, direction_update) = self.next_position(position, -1, 0, LEFT);
-                match self.map[quest.y as usize][(quest.x) as usize] {
-                    OPEN(_) => {
-                        self.map[quest.y as usize][quest.x as usize] = OPEN(Some(LEFT));
-                        return Some((quest, direction_update));
+                match self.map.get(quest.x as usize, quest.y as usize) {
+                    Some(OPEN(_)) => {
+                        self.map[(quest.x as usize, quest.y as usize)] = OPEN(Some(LEFT));
+                        Ok(Some((quest, direction_update)))
                     }
-                    SOLID => return None,
-                    _ => panic!(
-                        "how can there be an empty field!? {:?} {:?} => {:?} {:?}",
+                    Some(SOLID) => Ok(None),
+                    _ => Err(Error::no_solution(format!(
+                        "walked onto an empty field: {:?} {:?} => {:?} {:?}",
                         position, direction, quest, direction_update
-                    ),
+                    ))),
                 }
             }
             RIGHT => {
                 let (quest, direction_update) = self.next_position(position, 1, 0, RIGHT);
-                match self.map[quest.y as usize][(quest.x) as usize] {
-                    OPEN(_) => {
-                        self.map[quest.y as usize][quest.x as usize] = OPEN(Some(RIGHT));
-                        return Some((quest, direction_update));
+                match self.map.get(quest.x as usize, quest.y as usize) {
+                    Some(OPEN(_)) => {
+                        self.map[(quest.x as usize, quest.y as usize)] = OPEN(Some(RIGHT));
+                        Ok(Some((quest, direction_update)))
                     }
-                    SOLID => return None,
-                    _ => panic!(
-                        "how can there be an empty field!? {:?} {:?} => {:?} {:?}",
+                    Some(SOLID) => Ok(None),
+                    _ => Err(Error::no_solution(format!(
+                        "walked onto an empty field: {:?} {:?} => {:?} {:?}",
                         position, direction, quest, direction_update
-                    ),
+                    ))),
                 }
             }
             UP => {
                 let (quest, direction_update) = self.next_position(position, 0, -1, UP);
-                match self.map[quest.y as usize][(quest.x) as usize] {
-                    OPEN(_) => {
-                        self.map[quest.y as usize][quest.x as usize] = OPEN(Some(UP));
-                        return Some((quest, direction_update));
+                match self.map.get(quest.x as usize, quest.y as usize) {
+                    Some(OPEN(_)) => {
+                        self.map[(quest.x as usize, quest.y as usize)] = OPEN(Some(UP));
+                        Ok(Some((quest, direction_update)))
                     }
-                    SOLID => return None,
-                    _ => panic!(
-                        "how can there be an empty field!? {:?} {:?} => {:?} {:?}",
+                    Some(SOLID) => Ok(None),
+                    _ => Err(Error::no_solution(format!(
+                        "walked onto an empty field: {:?} {:?} => {:?} {:?}",
                         position, direction, quest, direction_update
-                    ),
+                    ))),
                 }
             }
             DOWN => {
                 let (quest, direction_update) = self.next_position(position, 0, 1, DOWN);
-                match self.map[quest.y as usize][(quest.x) as usize] {
-                    OPEN(_) => {
-                        self.map[quest.y as usize][quest.x as usize] = OPEN(Some(DOWN));
-                        return Some((quest, direction_update));
+                match self.map.get(quest.x as usize, quest.y as usize) {
+                    Some(OPEN(_)) => {
+                        self.map[(quest.x as usize, quest.y as usize)] = OPEN(Some(DOWN));
+                        Ok(Some((quest, direction_update)))
                     }
-                    SOLID => return None,
-                    _ => panic!(
-                        "how can there be an empty field!? {:?} {:?} => {:?} {:?}",
+                    Some(SOLID) => Ok(None),
+                    _ => Err(Error::no_solution(format!(
+                        "walked onto an empty field: {:?} {:?} => {:?} {:?}",
                         position, direction, quest, direction_update
-                    ),
+                    ))),
                 }
             }
         }
     }
 
-    fn go(&mut self, path: Vec<Go>) {
-        let mut current_position = if let Some(p) = self.current_position {
-            p.clone()
-        } else {
-            panic!("unknown current postion")
-        };
-        let mut current_direction = self.current_direction.clone();
+    /// follows the path and returns the final password
+    pub fn go(&mut self, path: Vec<Go>) -> Result<u32> {
+        let mut current_position = self
+            .current_position
+            .ok_or_else(|| Error::no_solution("there is no open tile to start at"))?;
+        let mut current_direction = self.current_direction;
         for path_element in path.iter() {
             match path_element {
                 Go::STEPS(n_steps) => {
                     for _ in 0..*n_steps {
                         if let Some(p) =
-                            self.go_next_position(&mut current_position, &current_direction)
+                            self.go_next_position(&current_position, &current_direction)?
                         {
                             (current_position, current_direction) = p;
                         } else {
@@ -351,7 +337,7 @@ impl Map {
                     (Direction::UP, Direction::RIGHT) => current_direction = Direction::RIGHT,
                     (Direction::DOWN, Direction::LEFT) => current_direction = Direction::RIGHT,
                     (Direction::DOWN, Direction::RIGHT) => current_direction = Direction::LEFT,
-                    _ => panic!(
+                    _ => unreachable!(
                         "unexpected directions {:?} => {:?}",
                         current_direction, direction
                     ),
@@ -360,36 +346,25 @@ impl Map {
             // self.current_position = Some(current_position);
         }
 
-        println!(
-            "final position: {:?} with direction: {:?}",
-            current_position, current_direction
-        );
-
-        println!(
-            "{}*1000 + {}*4 + {} = {}",
-            current_position.y + 1,
-            current_position.x + 1,
-            current_direction.facing_value(),
-            1000 * (current_position.y as u32 + 1)
-                + 4 * (current_position.x as u32 + 1)
-                + current_direction.facing_value() as u32
-        );
+        Ok(1000 * (current_position.y as u32 + 1)
+            + 4 * (current_position.x as u32 + 1)
+            + current_direction.facing_value() as u32)
     }
 }
 
 impl fmt::Display for Map {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        for y in 0..self.map.len() {
-            for x in 0..self.map[y].len() {
+        for y in 0..self.map.height() {
+            for x in 0..self.map.width() {
                 if let Some(p) = self.current_position {
                     if p.x == x as u16 && p.y == y as u16 {
                         write!(f, "O")?;
                         continue;
                     }
                 }
-                write!(f, "{}", self.map[y][x])?;
+                write!(f, "{}", self.map[(x, y)])?;
             }
-            write!(f, "\n")?;
+            writeln!(f)?;
         }
         Ok(())
     }
@@ -433,7 +408,7 @@ fn parser(input: &str) -> IResult<&str, Vec<Go>> {
     // previous approach of parsing
     // many0(alt((parser_direction, parser_digit)))(input)
     many1(alt((
-        u8.map(|num| Go::STEPS(num)),
+        u8.map(Go::STEPS),
         alt((
             char('L').map(|_| Go::TURN(Direction::LEFT)),
             char('R').map(|_| Go::TURN(Direction::RIGHT)),
@@ -441,63 +416,91 @@ fn parser(input: &str) -> IResult<&str, Vec<Go>> {
     )))(input)
 }
 
-fn aoc22_1() {
-    println!("solving AOC day 22 part 1");
-    let reader = BufReader::new(File::open("input-22").unwrap());
-    let mut map = Map::new();
-    let mut parse_map = true;
-    let mut path: Vec<Go> = vec![];
-    for (_index, line) in reader.lines().enumerate() {
-        let line = line.unwrap();
-        if line == "" {
-            parse_map = false;
-            continue;
-        }
-        if parse_map {
-            map.add_line(&line);
-        } else {
-            let result = parser(&line);
-            if let Ok(x) = result {
-                path = x.1;
-            } else {
-                panic!("couldn't parse move vector");
-            }
-        }
-    }
+pub struct Notes {
+    pub map: Map,
+    pub path: Vec<Go>,
+}
+
+fn aoc22_1(notes: &Notes) -> Result<u32> {
+    let mut map = notes.map.clone();
     map.gen_positionmap_part1();
-    map.fill_up();
-    map.go(path);
+    map.go(notes.path.clone())
 }
 
-fn aoc22_2() {
-    println!("solving AOC day 22 part 2");
-    let reader = BufReader::new(File::open("input-22").unwrap());
-    let mut map = Map::new();
-    let mut parse_map = true;
-    let mut path: Vec<Go> = vec![];
-    for (_index, line) in reader.lines().enumerate() {
-        let line = line.unwrap();
-        if line == "" {
-            parse_map = false;
-            continue;
-        }
-        if parse_map {
-            map.add_line(&line);
-        } else {
-            let result = parser(&line);
-            if let Ok(x) = result {
-                path = x.1;
+fn aoc22_2(notes: &Notes) -> Result<u32> {
+    let mut map = notes.map.clone();
+    map.gen_positionmap_part2()?;
+    map.go(notes.path.clone())
+}
+
+pub struct Aoc22;
+
+impl Solver for Aoc22 {
+    type Input = Notes;
+
+    fn parse(&self, input: &str) -> Result<Notes> {
+        let mut map_lines = vec![];
+        let mut parse_map = true;
+        let mut path: Vec<Go> = vec![];
+        for (index, line) in input.lines().enumerate() {
+            if line.is_empty() {
+                parse_map = false;
+                continue;
+            }
+            if parse_map {
+                map_lines.push(line);
             } else {
-                panic!("couldn't parse move vector");
+                let (rest, moves) =
+                    parser(line).map_err(|_| Error::line(index, "couldn't parse move vector"))?;
+                if !rest.is_empty() {
+                    return Err(Error::parse(
+                        index + 1,
+                        line.len() - rest.len() + 1,
+                        format!("unexpected move {:?}", rest),
+                    ));
+                }
+                path = moves;
             }
         }
+        let map = Map::new(&map_lines.join("\n"))?;
+        Ok(Notes { map, path })
+    }
+
+    fn part1(&self, notes: &Notes) -> Result<Answer> {
+        Ok(aoc22_1(notes)?.into())
+    }
+
+    fn part2(&self, notes: &Notes) -> Result<Answer> {
+        Ok(aoc22_2(notes)?.into())
     }
-    map.gen_positionmap_part2();
-    map.fill_up();
-    map.go(path);
 }
 
-pub fn aoc22() {
-    aoc22_1();
-    aoc22_2();
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = concat!(
+        "        ...#\n",
+        "        .#..\n",
+        "        #...\n",
+        "        ....\n",
+        "...#.......#\n",
+        "........#...\n",
+        "..#....#....\n",
+        "..........#.\n",
+        "        ...#....\n",
+        "        .....#..\n",
+        "        .#......\n",
+        "        ......#.\n",
+        "\n",
+        "10R5L5R10L4R5L5\n",
+    );
+
+    #[test]
+    fn example() {
+        let notes = Aoc22.parse(EXAMPLE).unwrap();
+        assert_eq!(Aoc22.part1(&notes).unwrap(), Answer::from(6032));
+        // part 2 only folds the layout of the real input, the example is laid out differently
+        assert!(matches!(Aoc22.part2(&notes), Err(Error::NoSolution(_))));
+    }
 }
@@ -1,49 +1,41 @@
-use min_max::*;
-use std::fmt;
-// use parse_int::parse;
-// use substring::Substring;
 use itertools::*;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::fmt;
+
+use crate::error::{Error, Result};
+use crate::grid::{BoundingBox, Grid};
+use crate::solver::{Answer, Solver};
 
 #[derive(Debug, Clone)]
 struct Pointer {
-    x: i32,
-    y: i32,
+    x: isize,
+    y: isize,
 }
 
 #[derive(Debug, Clone)]
 struct Elve {
-    x: i32,
-    y: i32,
+    x: isize,
+    y: isize,
 }
 
 impl Elve {
-    fn new(x: i32, y: i32) -> Elve {
+    fn new(x: isize, y: isize) -> Elve {
         Elve { x, y }
     }
 
     fn get_relative_position(&self, bounding_box: &BoundingBox) -> Pointer {
         Pointer {
-            x: (self.x - bounding_box.minx),
-            y: (self.y - bounding_box.miny),
+            x: (self.x - bounding_box.min_x),
+            y: (self.y - bounding_box.min_y),
         }
     }
 
-    fn can(options: Vec<Pointer>, map: &Vec<Vec<bool>>) -> bool {
-        options.iter().all(|o| {
-            if o.x >= 0
-                && o.y >= 0
-                && o.y < map.len() as i32
-                && o.x < map[o.y as usize].len() as i32
-            {
-                return !map[o.y as usize][o.x as usize];
-            }
-            return true;
-        })
+    fn can(options: Vec<Pointer>, map: &Grid<bool>) -> bool {
+        options
+            .iter()
+            .all(|o| !map.contains(o.x, o.y) || !map[(o.x as usize, o.y as usize)])
     }
 
-    fn can_north(p: &Pointer, map: &Vec<Vec<bool>>) -> bool {
+    fn can_north(p: &Pointer, map: &Grid<bool>) -> bool {
         if p.y == 0 {
             return true;
         }
@@ -59,8 +51,8 @@ impl Elve {
         )
     }
 
-    fn can_south(p: &Pointer, map: &Vec<Vec<bool>>) -> bool {
-        if p.y == map.len() as i32 - 1 {
+    fn can_south(p: &Pointer, map: &Grid<bool>) -> bool {
+        if p.y == map.height() as isize - 1 {
             return true;
         }
 
@@ -75,7 +67,7 @@ impl Elve {
         )
     }
 
-    fn can_west(p: &Pointer, map: &Vec<Vec<bool>>) -> bool {
+    fn can_west(p: &Pointer, map: &Grid<bool>) -> bool {
         if p.x == 0 {
             return true;
         }
@@ -91,8 +83,8 @@ impl Elve {
         )
     }
 
-    fn can_east(p: &Pointer, map: &Vec<Vec<bool>>) -> bool {
-        if p.x == map[p.y as usize].len() as i32 - 1 {
+    fn can_east(p: &Pointer, map: &Grid<bool>) -> bool {
+        if p.x == map.width() as isize - 1 {
             return true;
         }
 
@@ -107,7 +99,7 @@ impl Elve {
         )
     }
 
-    fn has_neighbors(p: &Pointer, map: &Vec<Vec<bool>>) -> bool {
+    fn has_neighbors(p: &Pointer, map: &Grid<bool>) -> bool {
         !Elve::can(
             iproduct!((-1..=1), (-1..=1))
                 .filter(|(x, y)| !(*x == 0 && *y == 0))
@@ -120,7 +112,7 @@ impl Elve {
         )
     }
 
-    fn turn(&self, round: usize, map: &Vec<Vec<bool>>, bounding_box: &BoundingBox) -> Elve {
+    fn turn(&self, round: usize, map: &Grid<bool>, bounding_box: &BoundingBox) -> Elve {
         let p = self.get_relative_position(bounding_box);
 
         if Elve::has_neighbors(&p, map) {
@@ -134,14 +126,14 @@ impl Elve {
                 }
             }
         }
-        return Elve::new(self.x, self.y);
+        Elve::new(self.x, self.y)
     }
 
     fn same_position(&self, other: &Elve) -> bool {
         self.x == other.x && self.y == other.y
     }
 
-    fn collides(&self, options: &Vec<Elve>) -> bool {
+    fn collides(&self, options: &[Elve]) -> bool {
         options.iter().filter(|o| self.same_position(o)).count() > 1
     }
 }
@@ -153,47 +145,7 @@ impl fmt::Display for Elve {
 }
 
 #[derive(Debug, Clone)]
-struct BoundingBox {
-    minx: i32,
-    maxx: i32,
-    miny: i32,
-    maxy: i32,
-}
-
-impl BoundingBox {
-    fn new(minx: i32, maxx: i32, miny: i32, maxy: i32) -> BoundingBox {
-        BoundingBox {
-            minx,
-            maxx,
-            miny,
-            maxy,
-        }
-    }
-
-    fn update(&mut self, x: i32, y: i32) {
-        self.minx = min!(self.minx, x);
-        self.maxx = max!(self.maxx, x);
-        self.miny = min!(self.miny, y);
-        self.maxy = max!(self.maxy, y);
-    }
-
-    fn get_smaller_copy(&self) -> BoundingBox {
-        let n = 2;
-        BoundingBox {
-            minx: self.minx + n,
-            maxx: self.maxx - n,
-            miny: self.miny + n,
-            maxy: self.maxy - n,
-        }
-    }
-
-    fn size(&self) -> i32 {
-        (1 + self.maxx - self.minx) * (1 + self.maxy - self.miny)
-    }
-}
-
-#[derive(Debug, Clone)]
-struct Map {
+pub struct Map {
     elves: Vec<Elve>,
     bounding_box: BoundingBox,
 }
@@ -202,25 +154,22 @@ impl Map {
     fn new() -> Map {
         Map {
             elves: vec![],
-            bounding_box: BoundingBox::new(1000, 0, 1000, 0),
+            bounding_box: BoundingBox::empty(),
         }
     }
-    fn add_elve(&mut self, row: i32, col: i32) {
-        self.elves.push(Elve {
-            x: col as i32,
-            y: row as i32,
-        });
+    fn add_elve(&mut self, row: isize, col: isize) {
+        self.elves.push(Elve { x: col, y: row });
         self.bounding_box.update(col, row)
     }
 
-    fn occupation(&self) -> Vec<Vec<bool>> {
-        (self.bounding_box.miny..=self.bounding_box.maxy)
-            .map(|y| {
-                (self.bounding_box.minx..=self.bounding_box.maxx)
-                    .map(|x| self.elves.iter().any(|e| e.x == x && e.y == y))
-                    .collect()
-            })
-            .collect()
+    fn occupation(&self) -> Grid<bool> {
+        let mut occupation =
+            Grid::new(self.bounding_box.width(), self.bounding_box.height(), false);
+        for elve in self.elves.iter() {
+            let p = elve.get_relative_position(&self.bounding_box);
+            occupation[(p.x as usize, p.y as usize)] = true;
+        }
+        occupation
     }
 
     fn turn(&mut self, round: usize) -> bool {
@@ -239,7 +188,7 @@ impl Map {
             })
             .collect();
 
-        let mut bounding_box = self.bounding_box.get_smaller_copy();
+        let mut bounding_box = BoundingBox::empty();
         let new_elves: Vec<Elve> = options
             .iter()
             .enumerate()
@@ -250,72 +199,103 @@ impl Map {
                     self.elves[index].clone()
                 };
                 bounding_box.update(e.x, e.y);
-                return e;
+                e
             })
             .collect();
 
         self.bounding_box = bounding_box;
         self.elves = new_elves;
-        return moved;
+        moved
     }
 
-    fn empty_fields(&self) -> i32 {
-        self.bounding_box.size() - self.elves.len() as i32
+    fn empty_fields(&self) -> isize {
+        self.bounding_box.area() as isize - self.elves.len() as isize
     }
 }
 
 impl fmt::Display for Map {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        for y in self.bounding_box.miny..=self.bounding_box.maxy {
-            for x in self.bounding_box.minx..=self.bounding_box.maxx {
+        for y in self.bounding_box.min_y..=self.bounding_box.max_y {
+            for x in self.bounding_box.min_x..=self.bounding_box.max_x {
                 if self.elves.iter().any(|e| e.x == x && e.y == y) {
                     write!(f, "#")?;
                 } else {
                     write!(f, " ")?;
                 }
             }
-            write!(f, "\n")?;
+            writeln!(f)?;
         }
         Ok(())
     }
 }
 
-fn aoc23_1(map: &mut Map) {
-    println!("solving AOC day 23 part 1");
-
+fn aoc23_1(map: &mut Map) -> isize {
     for round in 0..10 {
         map.turn(round);
     }
 
-    println!("empty ground: {}", map.empty_fields());
+    map.empty_fields()
 }
 
-fn aoc23_2(map: &mut Map) {
-    println!("solving AOC day 23 part 2");
+fn aoc23_2(map: &mut Map) -> usize {
+    // first round in which no elve moves
+    (0..).find(|round| !map.turn(*round)).unwrap() + 1
+}
 
-    for round in 0..1000 {
-        if !map.turn(round) {
-            println!("break as no elve moves after round {}", round + 1);
-            break;
+pub struct Aoc23;
+
+impl Solver for Aoc23 {
+    type Input = Map;
+
+    fn parse(&self, input: &str) -> Result<Map> {
+        let mut map = Map::new();
+
+        for (row, line) in input.lines().enumerate() {
+            for (col, c) in line.chars().enumerate() {
+                match c {
+                    '#' => map.add_elve(row as isize, col as isize),
+                    '.' => (),
+                    _ => {
+                        return Err(Error::parse(
+                            row + 1,
+                            col + 1,
+                            format!("unexpected tile {:?}", c),
+                        ))
+                    }
+                }
+            }
         }
-    }
 
-    println!("empty ground: {}", map.empty_fields());
-}
+        Ok(map)
+    }
 
-pub fn aoc23() {
-    let reader = BufReader::new(File::open("input-23").unwrap());
-    let mut map = Map::new();
+    fn part1(&self, map: &Map) -> Result<Answer> {
+        Ok(aoc23_1(&mut map.clone()).into())
+    }
 
-    for (row, line) in reader.lines().enumerate() {
-        let line = line.unwrap();
-        for (col, c) in line.chars().enumerate() {
-            if c == '#' {
-                map.add_elve(row as i32, col as i32);
-            }
-        }
+    fn part2(&self, map: &Map) -> Result<Answer> {
+        Ok(aoc23_2(&mut map.clone()).into())
     }
+}
 
-    aoc23_1(&mut map.clone());
-    aoc23_2(&mut map.clone());
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+....#..
+..###.#
+#...#.#
+.#...##
+#.###..
+##.#.##
+.#..#..
+";
+
+    #[test]
+    fn example() {
+        let map = Aoc23.parse(EXAMPLE).unwrap();
+        assert_eq!(Aoc23.part1(&map).unwrap(), Answer::from(110));
+        assert_eq!(Aoc23.part2(&map).unwrap(), Answer::from(20));
+    }
 }
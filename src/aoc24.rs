@@ -1,8 +1,10 @@
 use min_max::*;
 use rayon::prelude::*;
 use std::collections::{HashMap, HashSet};
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+
+use crate::error::{Error, Result};
+use crate::grid::Grid;
+use crate::solver::{Answer, Solver};
 
 #[derive(Debug, Copy, Clone, Hash, Eq, PartialEq)]
 struct Point {
@@ -30,39 +32,13 @@ impl Point {
         }
     }
 
-    fn get_options(&self, occ: &Vec<Vec<bool>>, maxx: usize, maxy: usize) -> Vec<Point> {
-        let mut options = vec![];
-        if self.x > 1 && !occ[self.y][self.x - 1] {
-            options.push(Point {
-                x: self.x - 1,
-                y: self.y,
-            });
-        }
-        if self.x < maxx && !occ[self.y][self.x + 1] {
-            options.push(Point {
-                x: self.x + 1,
-                y: self.y,
-            });
-        }
-        if self.y > 0 && !occ[self.y - 1][self.x] {
-            options.push(Point {
-                x: self.x,
-                y: self.y - 1,
-            });
-        }
-        if self.y < maxy && !occ[self.y + 1][self.x] {
-            options.push(Point {
-                x: self.x,
-                y: self.y + 1,
-            });
-        }
-        if !occ[self.y][self.x] {
-            options.push(Point {
-                x: self.x,
-                y: self.y,
-            });
-        }
-        return options;
+    /// the free positions reachable within one minute, including staying here
+    fn get_options(&self, occ: &Grid<bool>) -> Vec<Point> {
+        occ.neighbours4(self.x, self.y)
+            .chain([(self.x, self.y)])
+            .filter(|position| !occ[*position])
+            .map(|(x, y)| Point { x, y })
+            .collect()
     }
 }
 
@@ -81,40 +57,46 @@ struct Blizzard {
 }
 
 impl Blizzard {
-    fn new(position: &Point, direction: &char) -> Blizzard {
+    fn new(position: &Point, direction: &char) -> Result<Blizzard> {
         use Direction::*;
         let d = match direction {
             '^' => UP,
             '>' => RIGHT,
             'v' => DOWN,
             '<' => LEFT,
-            _ => panic!("unrecognised direction {}", direction),
+            _ => {
+                return Err(Error::parse(
+                    position.y + 1,
+                    position.x + 1,
+                    format!("unrecognised direction {:?}", direction),
+                ))
+            }
         };
-        Blizzard {
+        Ok(Blizzard {
             position: *position,
             direction: d,
-        }
+        })
     }
 
     fn position_at(&self, round: usize, maxx: usize, maxy: usize) -> Point {
         match self.direction {
             Direction::LEFT => self
                 .position
-                .translated_copy(round as i64 * -1, 0, maxx, maxy),
+                .translated_copy(-(round as i64), 0, maxx, maxy),
             Direction::RIGHT => self.position.translated_copy(round as i64, 0, maxx, maxy),
             Direction::UP => self
                 .position
-                .translated_copy(0, round as i64 * -1, maxx, maxy),
+                .translated_copy(0, -(round as i64), maxx, maxy),
             Direction::DOWN => self.position.translated_copy(0, round as i64, maxx, maxy),
         }
     }
 }
 
-struct Map {
+pub struct Map {
     blizzards: Vec<Blizzard>,
     start_position: Option<Point>,
     end_position: Option<Point>,
-    occupations: Option<HashMap<usize, Vec<Vec<bool>>>>,
+    occupations: Option<HashMap<usize, Grid<bool>>>,
     maxx: usize,
     maxy: usize,
 }
@@ -131,19 +113,20 @@ impl Map {
         }
     }
 
-    fn add_blizzard(&mut self, position: &Point, direction: &char) {
+    fn add_blizzard(&mut self, position: &Point, direction: &char) -> Result<()> {
         self.maxx = max!(self.maxx, position.x);
         self.maxy = max!(self.maxy, position.y);
 
         if *direction == '.' {
-            if self.start_position == None {
-                self.start_position = Some(position.clone());
+            if self.start_position.is_none() {
+                self.start_position = Some(*position);
             } else {
-                self.end_position = Some(position.clone());
+                self.end_position = Some(*position);
             }
         } else if *direction != '#' {
-            self.blizzards.push(Blizzard::new(position, direction));
+            self.blizzards.push(Blizzard::new(position, direction)?);
         }
+        Ok(())
     }
 
     fn get_repetition_cycle(&self) -> usize {
@@ -154,7 +137,7 @@ impl Map {
         let start = self.start_position.unwrap();
         let end = self.end_position.unwrap();
         let repetition_cycle = self.get_repetition_cycle();
-        let occupations: HashMap<usize, Vec<Vec<bool>>> = (0..repetition_cycle)
+        let occupations: HashMap<usize, Grid<bool>> = (0..repetition_cycle)
             .into_par_iter()
             .map(|iteration| {
                 let blizzard_positions: HashSet<_> = self
@@ -166,26 +149,15 @@ impl Map {
                     })
                     .collect();
 
-                let mut occupation_iteration = vec![];
-                for y in 0..=self.maxy {
-                    let mut occupation_iteration_row = vec![];
-                    for x in 0..=self.maxx {
-                        if start.equals(x, y) {
-                            occupation_iteration_row.push(false);
-                            continue;
-                        }
-                        if end.equals(x, y) {
-                            occupation_iteration_row.push(false);
-                            continue;
-                        }
-                        if x == 0 || y == 0 || x == self.maxx || y == self.maxy {
-                            occupation_iteration_row.push(true);
-                            continue;
-                        }
-                        let blizzard = blizzard_positions.contains(&(x, y));
-                        occupation_iteration_row.push(blizzard);
-                    }
-                    occupation_iteration.push(occupation_iteration_row);
+                let mut occupation_iteration = Grid::new(self.maxx + 1, self.maxy + 1, false);
+                for (x, y) in occupation_iteration.positions() {
+                    occupation_iteration[(x, y)] = if start.equals(x, y) || end.equals(x, y) {
+                        false
+                    } else if x == 0 || y == 0 || x == self.maxx || y == self.maxy {
+                        true
+                    } else {
+                        blizzard_positions.contains(&(x, y))
+                    };
                 }
                 (iteration, occupation_iteration)
             })
@@ -194,37 +166,34 @@ impl Map {
         self.occupations = Some(occupations);
     }
 
-    fn run_start_end(&self, round: usize) -> usize {
+    fn run_start_end(&self, round: usize) -> Result<usize> {
         let start = self.start_position.unwrap();
         let end = self.end_position.unwrap();
         self.run(&start, &end, round)
     }
 
-    fn run_end_start(&self, round: usize) -> usize {
+    fn run_end_start(&self, round: usize) -> Result<usize> {
         let start = self.start_position.unwrap();
         let end = self.end_position.unwrap();
         self.run(&end, &start, round)
     }
 
-    fn run(&self, start: &Point, end: &Point, start_round: usize) -> usize {
+    fn run(&self, start: &Point, end: &Point, start_round: usize) -> Result<usize> {
         let max_rounds = 1000;
         let repetition_cycle = self.get_repetition_cycle();
         let occupations = self.occupations.as_ref().unwrap();
 
-        let mut options: Vec<Point> = vec![start.clone()];
+        let mut options: Vec<Point> = vec![*start];
         let mut seen = HashSet::new();
 
         for round in start_round..start_round + max_rounds {
-            if round % 100 == 0 {
-                println!(".. round {} -- n options {}", round, options.len(),);
-            }
             let mut new_options: Vec<Point> = vec![];
             let occ = &occupations.get(&(round % occupations.len())).unwrap();
 
             for current in options.iter() {
-                for option in current.get_options(&occ, self.maxx, self.maxy) {
+                for option in current.get_options(occ) {
                     if option.equals(end.x, end.y) {
-                        return round;
+                        return Ok(round);
                     }
                     let hashkey = (option.x, option.y, round % repetition_cycle);
                     if !seen.contains(&hashkey) {
@@ -235,36 +204,68 @@ impl Map {
             }
             options = new_options;
         }
-        panic!("didn't manage to find a solution in {} rounds", max_rounds);
+        Err(Error::no_solution(format!(
+            "didn't manage to cross the valley in {} rounds",
+            max_rounds
+        )))
     }
 }
 
-fn aoc24_1(map: &Map) {
-    println!("solving AOC day 24 part 1");
-    println!("steps needed: {}", map.run_start_end(0));
+fn aoc24_1(map: &Map) -> Result<usize> {
+    map.run_start_end(0)
 }
 
-fn aoc24_2(map: &Map) {
-    println!("solving AOC day 24 part 2");
+fn aoc24_2(map: &Map) -> Result<usize> {
+    let mut steps = map.run_start_end(0)?;
+    steps = map.run_end_start(steps)?;
+    map.run_start_end(steps)
+}
 
-    let mut steps = map.run_start_end(0);
-    steps = map.run_end_start(steps);
-    steps = map.run_start_end(steps);
+pub struct Aoc24;
 
-    println!("steps needed: {}", steps);
-}
+impl Solver for Aoc24 {
+    type Input = Map;
 
-pub fn aoc24() {
-    let reader = BufReader::new(File::open("input-24").unwrap());
-    let mut map = Map::new();
-    for (index, line) in reader.lines().enumerate() {
-        let line = line.unwrap();
-        for (i, c) in line.chars().enumerate() {
-            map.add_blizzard(&Point { x: i, y: index }, &c);
+    fn parse(&self, input: &str) -> Result<Map> {
+        let mut map = Map::new();
+        for (index, line) in input.lines().enumerate() {
+            for (i, c) in line.chars().enumerate() {
+                map.add_blizzard(&Point { x: i, y: index }, &c)?;
+            }
+        }
+        if map.end_position.is_none() || map.maxx < 2 || map.maxy < 2 {
+            return Err(Error::no_solution("the valley has no entrance and exit"));
         }
+        map.calc_occupation();
+        Ok(map)
     }
-    map.calc_occupation();
 
-    aoc24_1(&map);
-    aoc24_2(&map);
+    fn part1(&self, map: &Map) -> Result<Answer> {
+        Ok(aoc24_1(map)?.into())
+    }
+
+    fn part2(&self, map: &Map) -> Result<Answer> {
+        Ok(aoc24_2(map)?.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+#.######
+#>>.<^<#
+#.<..<<#
+#>v.><>#
+#<^v^^>#
+######.#
+";
+
+    #[test]
+    fn example() {
+        let map = Aoc24.parse(EXAMPLE).unwrap();
+        assert_eq!(Aoc24.part1(&map).unwrap(), Answer::from(18));
+        assert_eq!(Aoc24.part2(&map).unwrap(), Answer::from(54));
+    }
 }
@@ -1,6 +1,7 @@
-use core::panic;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use crate::error::{Error, Result};
+use crate::solver::{Answer, Solver};
+
+const SNAFU_DIGITS: &str = "=-012";
 
 fn char_to_value(c: char) -> i64 {
     match c {
@@ -9,7 +10,7 @@ fn char_to_value(c: char) -> i64 {
         '0' => 0,
         '1' => 1,
         '2' => 2,
-        _ => panic!("do not understand char {}", c),
+        _ => unreachable!("digits are validated while parsing"),
     }
 }
 
@@ -20,58 +21,114 @@ fn value_to_char(n: i64) -> (char, i64) {
         2 => ('2', 2),
         3 => ('=', -2),
         4 => ('-', -1),
-        _ => panic!("do not understand value {}", n),
+        _ => unreachable!("{} is not a remainder of 5", n),
     }
 }
 
-fn number_to_snafu(n: i64) -> String {
+/// formats `n` in the balanced base 5 of the SNAFU numbers
+pub fn number_to_snafu(n: i64) -> String {
     let mut s = vec![];
     let mut n = n;
-    // let mut snafu = "";
     while n > 0 {
         let m = n % 5;
         let (c, m) = value_to_char(m);
-        // println!("-- {} - {} -> {}", n, m, c);
         s.push(c);
         n = (n - m) / 5;
-        // snafu += c;
     }
-    // println!("===> {:?}", s);
     s.iter().rev().collect()
 }
 
-fn aoc25_1() {
-    println!("solving AOC day 25 part 1");
-    let reader = BufReader::new(File::open("input-25").unwrap());
+fn aoc25_1(lines: &[String]) -> String {
     let mut score = 0;
-    for (_index, line) in reader.lines().enumerate() {
-        let line = line.unwrap();
-        // score += line.len();
+    for line in lines.iter() {
         let mut sum = 0;
         for (i, c) in line.chars().enumerate() {
             sum += 5_i64.pow((line.len() - i - 1) as u32) * char_to_value(c);
         }
-        // println!("{} => {}", line, sum);
         score += sum;
     }
 
-    println!("solution: {}", score);
-    println!("snafu: {}", number_to_snafu(score));
+    number_to_snafu(score)
 }
 
-fn aoc25_2() {
-    println!("solving AOC day 25 part 2");
-    let reader = BufReader::new(File::open("input-25").unwrap());
+fn aoc25_2(lines: &[String]) -> usize {
     let mut score = 0;
-    for (_index, line) in reader.lines().enumerate() {
-        let line = line.unwrap();
+    for line in lines.iter() {
         score += line.len();
     }
 
-    println!("solution: {}", score);
+    score
 }
 
-pub fn aoc25() {
-    aoc25_1();
-    aoc25_2();
+pub struct Aoc25;
+
+impl Solver for Aoc25 {
+    type Input = Vec<String>;
+
+    fn parse(&self, input: &str) -> Result<Vec<String>> {
+        let mut lines = vec![];
+        for (index, line) in input.lines().enumerate() {
+            if let Some(column) = line.find(|c| !SNAFU_DIGITS.contains(c)) {
+                return Err(Error::parse(
+                    index + 1,
+                    column + 1,
+                    format!("do not understand char {:?}", &line[column..column + 1]),
+                ));
+            }
+            lines.push(line.to_string());
+        }
+        Ok(lines)
+    }
+
+    fn part1(&self, lines: &Vec<String>) -> Result<Answer> {
+        Ok(aoc25_1(lines).into())
+    }
+
+    fn part2(&self, lines: &Vec<String>) -> Result<Answer> {
+        Ok(aoc25_2(lines).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+1=-0-2
+12111
+2=0=
+21
+2=01
+111
+20012
+112
+1=-1=
+1-12
+12
+1=
+122
+";
+
+    #[test]
+    fn example() {
+        let lines = Aoc25.parse(EXAMPLE).unwrap();
+        assert_eq!(Aoc25.part1(&lines).unwrap(), Answer::from("2=-1=0"));
+    }
+
+    #[test]
+    fn snafu() {
+        let numbers = [
+            (1, "1"),
+            (3, "1="),
+            (8, "2="),
+            (10, "20"),
+            (15, "1=0"),
+            (2022, "1=11-2"),
+            (12345, "1-0---0"),
+            (314159265, "1121-1110-1=0"),
+        ];
+        for (n, snafu) in numbers {
+            assert_eq!(number_to_snafu(n), snafu);
+        }
+    }
 }
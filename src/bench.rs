@@ -0,0 +1,184 @@
+use std::time::Duration;
+
+use crate::error::Result;
+use crate::runner;
+use crate::solver::Puzzle;
+
+/// default number of runs per day
+pub const DEFAULT_RUNS: usize = 10;
+
+/// Timings of one phase over several runs.
+///
+/// Runs outside of the Tukey fences (1.5 times the interquartile range
+/// below the first or above the third quartile) are dropped as outliers
+/// before the statistics are computed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub outliers: usize,
+}
+
+impl Stats {
+    pub fn new(timings: &[Duration]) -> Stats {
+        let mut sorted = timings.to_vec();
+        sorted.sort();
+
+        let (low, high) = if sorted.len() < 4 {
+            (Duration::ZERO, Duration::MAX)
+        } else {
+            let q1 = quantile(&sorted, 0.25);
+            let q3 = quantile(&sorted, 0.75);
+            let fence = (q3 - q1).mul_f64(1.5);
+            (q1.saturating_sub(fence), q3.saturating_add(fence))
+        };
+        let kept: Vec<Duration> = sorted
+            .iter()
+            .filter(|t| (low..=high).contains(*t))
+            .copied()
+            .collect();
+
+        Stats {
+            min: kept.first().copied().unwrap_or_default(),
+            median: quantile(&kept, 0.5),
+            mean: kept.iter().sum::<Duration>() / kept.len().max(1) as u32,
+            outliers: sorted.len() - kept.len(),
+        }
+    }
+
+    fn to_json(&self) -> String {
+        format!(
+            "{{\"min_ns\": {}, \"median_ns\": {}, \"mean_ns\": {}, \"outliers\": {}}}",
+            self.min.as_nanos(),
+            self.median.as_nanos(),
+            self.mean.as_nanos(),
+            self.outliers
+        )
+    }
+}
+
+/// linearly interpolated quantile `q` of the `sorted` timings
+fn quantile(sorted: &[Duration], q: f64) -> Duration {
+    if sorted.is_empty() {
+        return Duration::ZERO;
+    }
+    let position = q * (sorted.len() - 1) as f64;
+    let lower = sorted[position.floor() as usize];
+    let upper = sorted[position.ceil() as usize];
+    lower + (upper - lower).mul_f64(position.fract())
+}
+
+/// timings of every phase of one day
+#[derive(Debug, Clone)]
+pub struct DayBench {
+    pub day: u32,
+    pub runs: usize,
+    pub parse: Stats,
+    pub part1: Stats,
+    pub part2: Stats,
+}
+
+pub fn bench_day(day: u32, solver: &dyn Puzzle, input: &str, runs: usize) -> Result<DayBench> {
+    let mut parse = vec![];
+    let mut part1 = vec![];
+    let mut part2 = vec![];
+    for _ in 0..runs {
+        let result = runner::run_day(day, solver, input)?;
+        parse.push(result.parse_time);
+        part1.push(result.part1_time);
+        part2.push(result.part2_time);
+    }
+
+    Ok(DayBench {
+        day,
+        runs,
+        parse: Stats::new(&parse),
+        part1: Stats::new(&part1),
+        part2: Stats::new(&part2),
+    })
+}
+
+/// renders the benchmarks as one block per day
+pub fn report(benches: &[DayBench]) -> String {
+    let mut lines = vec![];
+    for bench in benches {
+        lines.push(format!("day {} ({} runs)", bench.day, bench.runs));
+        for (phase, stats) in [
+            ("parse", &bench.parse),
+            ("part 1", &bench.part1),
+            ("part 2", &bench.part2),
+        ] {
+            lines.push(format!(
+                "  {:<6}  min {:>10.2?}  median {:>10.2?}  mean {:>10.2?}  outliers {}",
+                phase, stats.min, stats.median, stats.mean, stats.outliers
+            ));
+        }
+    }
+    lines.join("\n")
+}
+
+/// renders the benchmarks as JSON, all durations in nanoseconds
+pub fn to_json(benches: &[DayBench]) -> String {
+    let days: Vec<String> = benches
+        .iter()
+        .map(|bench| {
+            format!(
+                "    {{\"day\": {}, \"runs\": {}, \"parse\": {}, \"part1\": {}, \"part2\": {}}}",
+                bench.day,
+                bench.runs,
+                bench.parse.to_json(),
+                bench.part1.to_json(),
+                bench.part2.to_json()
+            )
+        })
+        .collect();
+    format!("{{\n  \"days\": [\n{}\n  ]\n}}", days.join(",\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn millis(timings: &[u64]) -> Vec<Duration> {
+        timings.iter().map(|t| Duration::from_millis(*t)).collect()
+    }
+
+    #[test]
+    fn stats() {
+        let stats = Stats::new(&millis(&[12, 10, 11, 13, 10, 100]));
+        assert_eq!(stats.outliers, 1);
+        assert_eq!(stats.min, Duration::from_millis(10));
+        assert_eq!(stats.median, Duration::from_millis(11));
+        assert_eq!(stats.mean, Duration::from_nanos(11_200_000));
+    }
+
+    #[test]
+    fn few_runs_keep_everything() {
+        let stats = Stats::new(&millis(&[1, 100]));
+        assert_eq!(stats.outliers, 0);
+        assert_eq!(stats.median, Duration::from_nanos(50_500_000));
+        assert_eq!(Stats::new(&[]).mean, Duration::ZERO);
+    }
+
+    #[test]
+    fn json() {
+        let stats = Stats::new(&millis(&[1]));
+        let bench = DayBench {
+            day: 3,
+            runs: 1,
+            parse: stats.clone(),
+            part1: stats.clone(),
+            part2: stats,
+        };
+        let phase =
+            "{\"min_ns\": 1000000, \"median_ns\": 1000000, \"mean_ns\": 1000000, \"outliers\": 0}";
+        assert_eq!(
+            to_json(&[bench]),
+            format!(
+                "{{\n  \"days\": [\n    {{\"day\": 3, \"runs\": 1, \"parse\": {0}, \"part1\": {0}, \"part2\": {0}}}\n  ]\n}}",
+                phase
+            )
+        );
+    }
+}
@@ -0,0 +1,181 @@
+use std::path::PathBuf;
+
+use aoc_2022_rust::input::{InputSource, DEFAULT_SCHEME};
+use aoc_2022_rust::{Error, Result};
+
+pub const USAGE: &str = "usage: aoc-2022-rust [DAY] [OPTIONS]
+       aoc-2022-rust run DAYS [OPTIONS]
+       aoc-2022-rust bench DAYS [--runs N] [--json] [OPTIONS]
+
+  DAY                     day to solve, defaults to today
+  run DAYS                solve several days and print a summary, DAYS is
+                          `all` or a comma separated list of days and
+                          ranges, e.g. `1-25` or `3,7,12`
+  bench DAYS              solve the days several times and report how long
+                          parsing and both parts take
+
+options:
+  --input <FILE>          read the input from FILE, `-` reads from stdin
+  -                       same as `--input -`
+  --inputs-dir <DIR>      read the input of each day from DIR (default: .)
+  --input-scheme <NAME>   file name of the inputs in DIR, `{day}` and
+                          `{day:02}` are replaced by the day
+                          (default: input-{day:02})
+  --check                 compare the answers with the confirmed ones and
+                          report PASS, FAIL or MISSING for every part
+  --answers <FILE>        file with the confirmed answers used by --check
+                          (default: answers.toml)
+  --runs <N>              number of runs per day for bench (default: 10)
+  --json                  print the bench results as JSON
+  -h, --help              print this help";
+
+/// the days selected on the command line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Days {
+    /// a single day, `None` means today
+    Single(Option<u32>),
+    /// every day known to the registry
+    All,
+    List(Vec<u32>),
+}
+
+impl Default for Days {
+    fn default() -> Days {
+        Days::Single(None)
+    }
+}
+
+/// the parsed command line
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Options {
+    pub days: Days,
+    pub input: InputSource,
+    /// compare the answers with the confirmed ones
+    pub check: bool,
+    /// file with the confirmed answers, `None` for the default file
+    pub answers: Option<PathBuf>,
+    /// benchmark the days instead of just solving them
+    pub bench: bool,
+    /// number of bench runs, `None` for the default
+    pub runs: Option<usize>,
+    pub json: bool,
+    pub help: bool,
+}
+
+pub fn parse_args<I: IntoIterator<Item = String>>(args: I) -> Result<Options> {
+    let mut options = Options::default();
+    let mut input: Option<InputSource> = None;
+    let mut dir: Option<PathBuf> = None;
+    let mut scheme: Option<String> = None;
+
+    let mut args = args.into_iter().peekable();
+    if let Some(command) = args.next_if(|arg| arg == "run" || arg == "bench") {
+        let spec = value_of(&command, args.next())?;
+        options.days = parse_days(&spec)?;
+        options.bench = command == "bench";
+    }
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => options.help = true,
+            "-" => input = Some(InputSource::Stdin),
+            "--input" => {
+                let value = value_of(&arg, args.next())?;
+                input = Some(if value == "-" {
+                    InputSource::Stdin
+                } else {
+                    InputSource::File(PathBuf::from(value))
+                });
+            }
+            "--inputs-dir" => dir = Some(PathBuf::from(value_of(&arg, args.next())?)),
+            "--input-scheme" => scheme = Some(value_of(&arg, args.next())?),
+            "--check" => options.check = true,
+            "--answers" => options.answers = Some(PathBuf::from(value_of(&arg, args.next())?)),
+            "--runs" => {
+                let value = value_of(&arg, args.next())?;
+                options.runs = match value.parse::<usize>() {
+                    Ok(runs) if runs > 0 => Some(runs),
+                    _ => return Err(Error::Usage(format!("invalid number of runs {:?}", value))),
+                };
+            }
+            "--json" => options.json = true,
+            _ if arg.starts_with('-') => {
+                return Err(Error::Usage(format!("unknown option {}", arg)))
+            }
+            _ => {
+                if options.days != Days::Single(None) {
+                    return Err(Error::Usage(format!("unexpected argument {}", arg)));
+                }
+                options.days = Days::Single(Some(parse_day(&arg)?));
+            }
+        }
+    }
+
+    options.input = match (input, dir, scheme) {
+        (Some(_), Some(_), _) | (Some(_), _, Some(_)) => {
+            return Err(Error::Usage(
+                "--input cannot be combined with --inputs-dir or --input-scheme".to_string(),
+            ))
+        }
+        (Some(input), None, None) => input,
+        (None, dir, scheme) => InputSource::Dir {
+            dir: dir.unwrap_or_else(|| PathBuf::from(".")),
+            scheme: scheme.unwrap_or_else(|| DEFAULT_SCHEME.to_string()),
+        },
+    };
+
+    if !options.bench && (options.runs.is_some() || options.json) {
+        return Err(Error::Usage(
+            "--runs and --json can only be used with bench".to_string(),
+        ));
+    }
+    if options.bench && options.check {
+        return Err(Error::Usage(
+            "--check cannot be combined with bench".to_string(),
+        ));
+    }
+
+    let several_days = match &options.days {
+        Days::Single(_) => false,
+        Days::All => true,
+        Days::List(days) => days.len() > 1,
+    };
+    if several_days && !matches!(options.input, InputSource::Dir { .. }) {
+        return Err(Error::Usage(
+            "--input and stdin can only be used when solving a single day".to_string(),
+        ));
+    }
+
+    Ok(options)
+}
+
+/// parses `all` or a comma separated list of days and ranges like `1-3,7`
+pub fn parse_days(spec: &str) -> Result<Days> {
+    if spec == "all" {
+        return Ok(Days::All);
+    }
+
+    let mut days = vec![];
+    for part in spec.split(',') {
+        if let Some((from, to)) = part.split_once('-') {
+            let from = parse_day(from)?;
+            let to = parse_day(to)?;
+            if from > to {
+                return Err(Error::Usage(format!("invalid range of days {}", part)));
+            }
+            days.extend(from..=to);
+        } else {
+            days.push(parse_day(part)?);
+        }
+    }
+    Ok(Days::List(days))
+}
+
+fn parse_day(day: &str) -> Result<u32> {
+    day.parse::<u32>()
+        .map_err(|_| Error::Usage(format!("invalid day {:?}", day)))
+}
+
+fn value_of(option: &str, value: Option<String>) -> Result<String> {
+    value.ok_or_else(|| Error::Usage(format!("{} expects a value", option)))
+}
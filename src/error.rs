@@ -0,0 +1,107 @@
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+/// Everything that can go wrong while solving a puzzle.
+#[derive(Debug)]
+pub enum Error {
+    /// the input could not be read, `path` is `None` for stdin
+    MissingInput {
+        path: Option<PathBuf>,
+        source: io::Error,
+    },
+    /// the input is malformed, `line` and `column` start at 1
+    Parse {
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    /// the puzzle has no solution for the given input
+    NoSolution(String),
+    /// a solver was configured with values it cannot work with
+    Invalid(String),
+    /// there is no solver registered for the day
+    UnknownDay { day: u32, known: Vec<u32> },
+    /// the command line could not be understood
+    Usage(String),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl Error {
+    pub fn parse(line: usize, column: usize, message: impl Into<String>) -> Error {
+        Error::Parse {
+            line,
+            column,
+            message: message.into(),
+        }
+    }
+
+    /// a parse error for a line as a whole, `index` is the 0-based line index
+    pub fn line(index: usize, message: impl Into<String>) -> Error {
+        Error::parse(index + 1, 1, message)
+    }
+
+    pub fn no_solution(message: impl Into<String>) -> Error {
+        Error::NoSolution(message.into())
+    }
+
+    pub fn invalid(message: impl Into<String>) -> Error {
+        Error::Invalid(message.into())
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::MissingInput {
+                path: Some(path),
+                source,
+            } => write!(f, "cannot read input {}: {}", path.display(), source),
+            Error::MissingInput { path: None, source } => {
+                write!(f, "cannot read input from stdin: {}", source)
+            }
+            Error::Parse {
+                line,
+                column,
+                message,
+            } => write!(
+                f,
+                "parse error at line {}, column {}: {}",
+                line, column, message
+            ),
+            Error::NoSolution(message) => write!(f, "no solution found: {}", message),
+            Error::Invalid(message) => write!(f, "invalid configuration: {}", message),
+            Error::UnknownDay { day, known } => {
+                write!(f, "no solver for day {}, known days: {:?}", day, known)
+            }
+            Error::Usage(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::MissingInput { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// parses the number `s` found in the line with 0-based `index`, starting at
+/// the 0-based `offset` within that line
+pub fn number<T>(s: &str, index: usize, offset: usize) -> Result<T>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    s.parse::<T>().map_err(|e| {
+        Error::parse(
+            index + 1,
+            offset + 1,
+            format!("invalid number {:?}: {}", s, e),
+        )
+    })
+}
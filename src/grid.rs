@@ -0,0 +1,382 @@
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+use crate::error::{Error, Result};
+
+/// offsets of the four direct neighbours, up, right, down and left
+pub const NEIGHBOURS4: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+/// offsets of all eight neighbours, clockwise starting top left
+pub const NEIGHBOURS8: [(isize, isize); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+];
+
+/// A rectangular grid of cells, stored row by row.
+///
+/// Cells are addressed by `(x, y)`, `x` is the column and `y` the row,
+/// both starting at 0 in the top left corner.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(width: usize, height: usize, value: T) -> Grid<T> {
+        Grid {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+
+    /// appends `n` rows filled with `value` at the bottom
+    pub fn push_rows(&mut self, n: usize, value: T) {
+        self.cells.resize(self.cells.len() + n * self.width, value);
+        self.height += n;
+    }
+
+    /// like [`Grid::parse`], but pads short rows with `fill`
+    pub fn parse_ragged(
+        input: &str,
+        fill: T,
+        cell: impl FnMut(char) -> Option<T>,
+    ) -> Result<Grid<T>> {
+        let rows = parse_rows(input, cell)?;
+        let width = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+        let cells = rows
+            .into_iter()
+            .flat_map(|mut row| {
+                row.resize(width, fill.clone());
+                row
+            })
+            .collect::<Vec<T>>();
+        Ok(Grid {
+            width,
+            height: cells.len().checked_div(width).unwrap_or(0),
+            cells,
+        })
+    }
+}
+
+impl<T> Grid<T> {
+    /// builds a grid from its rows, `None` if they are not all of the same length
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Option<Grid<T>> {
+        let width = rows.first().map(|row| row.len()).unwrap_or(0);
+        if rows.iter().any(|row| row.len() != width) {
+            return None;
+        }
+        let height = rows.len();
+        Some(Grid {
+            width,
+            height,
+            cells: rows.into_iter().flatten().collect(),
+        })
+    }
+
+    /// parses a character map, one row per line, `cell` turns a character
+    /// into a cell or returns `None` if the character is not allowed
+    pub fn parse(input: &str, cell: impl FnMut(char) -> Option<T>) -> Result<Grid<T>> {
+        let rows = parse_rows(input, cell)?;
+        let width = rows.first().map(|row| row.len()).unwrap_or(0);
+        if let Some(index) = rows.iter().position(|row| row.len() != width) {
+            return Err(Error::line(
+                index,
+                format!("expected {} columns, found {}", width, rows[index].len()),
+            ));
+        }
+        Ok(Grid::from_rows(rows).unwrap())
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, x: isize, y: isize) -> bool {
+        x >= 0 && y >= 0 && (x as usize) < self.width && (y as usize) < self.height
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<&T> {
+        if x < self.width && y < self.height {
+            self.cells.get(y * self.width + x)
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut T> {
+        if x < self.width && y < self.height {
+            self.cells.get_mut(y * self.width + x)
+        } else {
+            None
+        }
+    }
+
+    /// the cell at `(x, y)` moved by `(dx, dy)`, if that is still on the grid
+    pub fn offset(&self, x: usize, y: usize, dx: isize, dy: isize) -> Option<(usize, usize)> {
+        let (x, y) = (x as isize + dx, y as isize + dy);
+        self.contains(x, y).then_some((x as usize, y as usize))
+    }
+
+    /// the up to four direct neighbours of `(x, y)` on the grid
+    pub fn neighbours4(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        NEIGHBOURS4
+            .iter()
+            .filter_map(move |(dx, dy)| self.offset(x, y, *dx, *dy))
+    }
+
+    /// the up to eight neighbours of `(x, y)` on the grid, including the diagonal ones
+    pub fn neighbours8(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        NEIGHBOURS8
+            .iter()
+            .filter_map(move |(dx, dy)| self.offset(x, y, *dx, *dy))
+    }
+
+    /// all coordinates, row by row
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    /// all cells with their coordinates, row by row
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    /// the first coordinate whose cell matches `predicate`
+    pub fn find(&self, mut predicate: impl FnMut(&T) -> bool) -> Option<(usize, usize)> {
+        self.iter()
+            .find(|(_, cell)| predicate(cell))
+            .map(|(position, _)| position)
+    }
+
+    /// the smallest box around all cells matching `predicate`
+    pub fn bounding_box(&self, mut predicate: impl FnMut(&T) -> bool) -> BoundingBox {
+        self.iter()
+            .filter(|(_, cell)| predicate(cell))
+            .map(|((x, y), _)| (x as isize, y as isize))
+            .collect()
+    }
+}
+
+fn parse_rows<T>(input: &str, mut cell: impl FnMut(char) -> Option<T>) -> Result<Vec<Vec<T>>> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(index, line)| {
+            line.chars()
+                .enumerate()
+                .map(|(column, c)| {
+                    cell(c).ok_or_else(|| {
+                        Error::parse(index + 1, column + 1, format!("unexpected {:?}", c))
+                    })
+                })
+                .collect()
+        })
+        .collect()
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (x, y): (usize, usize)) -> &T {
+        self.get(x, y).unwrap_or_else(|| {
+            panic!(
+                "({}, {}) is outside of the {}x{} grid",
+                x, y, self.width, self.height
+            )
+        })
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut T {
+        let (width, height) = (self.width, self.height);
+        self.get_mut(x, y)
+            .unwrap_or_else(|| panic!("({}, {}) is outside of the {}x{} grid", x, y, width, height))
+    }
+}
+
+/// renders every cell using its `Display`, one line per row
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// The smallest box around a set of points, empty until the first update.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BoundingBox {
+    pub min_x: isize,
+    pub max_x: isize,
+    pub min_y: isize,
+    pub max_y: isize,
+}
+
+impl Default for BoundingBox {
+    fn default() -> BoundingBox {
+        BoundingBox::empty()
+    }
+}
+
+impl BoundingBox {
+    pub fn empty() -> BoundingBox {
+        BoundingBox {
+            min_x: isize::MAX,
+            max_x: isize::MIN,
+            min_y: isize::MAX,
+            max_y: isize::MIN,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.min_x > self.max_x || self.min_y > self.max_y
+    }
+
+    /// grows the box so that it contains `(x, y)`
+    pub fn update(&mut self, x: isize, y: isize) {
+        self.min_x = self.min_x.min(x);
+        self.max_x = self.max_x.max(x);
+        self.min_y = self.min_y.min(y);
+        self.max_y = self.max_y.max(y);
+    }
+
+    pub fn contains(&self, x: isize, y: isize) -> bool {
+        (self.min_x..=self.max_x).contains(&x) && (self.min_y..=self.max_y).contains(&y)
+    }
+
+    pub fn width(&self) -> usize {
+        if self.is_empty() {
+            0
+        } else {
+            self.min_x.abs_diff(self.max_x) + 1
+        }
+    }
+
+    pub fn height(&self) -> usize {
+        if self.is_empty() {
+            0
+        } else {
+            self.min_y.abs_diff(self.max_y) + 1
+        }
+    }
+
+    pub fn area(&self) -> usize {
+        self.width() * self.height()
+    }
+}
+
+impl FromIterator<(isize, isize)> for BoundingBox {
+    fn from_iter<I: IntoIterator<Item = (isize, isize)>>(points: I) -> BoundingBox {
+        let mut bounding_box = BoundingBox::empty();
+        for (x, y) in points {
+            bounding_box.update(x, y);
+        }
+        bounding_box
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn digits(input: &str) -> Result<Grid<u32>> {
+        Grid::parse(input, |c| c.to_digit(10))
+    }
+
+    #[test]
+    fn parse() {
+        let grid = digits("123\n456\n").unwrap();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(0, 1)], 4);
+        assert_eq!(grid.get(2, 0), Some(&3));
+        assert_eq!(grid.get(3, 0), None);
+        assert_eq!(grid.row(1), &[4, 5, 6]);
+        assert_eq!(grid.to_string(), "123\n456\n");
+    }
+
+    #[test]
+    fn parse_errors() {
+        match digits("12\n3x\n") {
+            Err(Error::Parse { line, column, .. }) => assert_eq!((line, column), (2, 2)),
+            other => panic!("expected a parse error, got {:?}", other),
+        }
+        match digits("12\n345\n") {
+            Err(Error::Parse { line, .. }) => assert_eq!(line, 2),
+            other => panic!("expected a parse error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parse_ragged() {
+        let grid = Grid::parse_ragged("  #\n#\n", ' ', Some).unwrap();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid.to_string(), "  #\n#  \n");
+    }
+
+    #[test]
+    fn neighbours() {
+        let grid = digits("123\n456\n789\n").unwrap();
+        let values = |positions: Vec<(usize, usize)>| -> Vec<u32> {
+            positions.into_iter().map(|p| grid[p]).collect()
+        };
+        assert_eq!(values(grid.neighbours4(0, 0).collect()), vec![2, 4]);
+        assert_eq!(values(grid.neighbours4(1, 1).collect()), vec![2, 6, 8, 4]);
+        assert_eq!(values(grid.neighbours8(2, 2).collect()), vec![5, 6, 8]);
+        assert_eq!(grid.neighbours8(1, 1).count(), 8);
+    }
+
+    #[test]
+    fn bounding_box() {
+        let grid = Grid::parse("....\n.#..\n...#\n", |c| Some(c == '#')).unwrap();
+        let bounding_box = grid.bounding_box(|cell| *cell);
+        assert_eq!((bounding_box.min_x, bounding_box.min_y), (1, 1));
+        assert_eq!((bounding_box.width(), bounding_box.height()), (3, 2));
+        assert!(bounding_box.contains(2, 2));
+        assert!(!bounding_box.contains(0, 2));
+        assert_eq!(BoundingBox::empty().area(), 0);
+    }
+
+    #[test]
+    fn push_rows() {
+        let mut grid = Grid::new(2, 1, false);
+        grid.push_rows(2, true);
+        assert_eq!(grid.height(), 3);
+        assert!(grid[(1, 2)]);
+        assert!(!grid[(1, 0)]);
+    }
+}
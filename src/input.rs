@@ -0,0 +1,143 @@
+use std::fs;
+use std::io::{self, BufRead, Read};
+use std::path::PathBuf;
+
+use crate::error::{Error, Result};
+
+/// default naming scheme of the input files, `{day}` is replaced by the day
+/// and `{day:02}` by the zero-padded day
+pub const DEFAULT_SCHEME: &str = "input-{day:02}";
+
+/// Where the puzzle input of a day comes from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputSource {
+    /// a single file, used for whatever day is solved
+    File(PathBuf),
+    /// read everything from standard input
+    Stdin,
+    /// one file per day in `dir`, named according to `scheme`
+    Dir { dir: PathBuf, scheme: String },
+}
+
+impl Default for InputSource {
+    fn default() -> InputSource {
+        InputSource::Dir {
+            dir: PathBuf::from("."),
+            scheme: DEFAULT_SCHEME.to_string(),
+        }
+    }
+}
+
+impl InputSource {
+    /// the file the input of `day` is read from, if any
+    pub fn path(&self, day: u32) -> Option<PathBuf> {
+        match self {
+            InputSource::File(path) => Some(path.clone()),
+            InputSource::Stdin => None,
+            InputSource::Dir { dir, scheme } => Some(dir.join(file_name(scheme, day))),
+        }
+    }
+
+    pub fn read(&self, day: u32) -> Result<String> {
+        let path = self.path(day);
+        let input = match &path {
+            Some(path) => fs::read_to_string(path),
+            None => {
+                let mut input = String::new();
+                io::stdin().read_to_string(&mut input).map(|_| input)
+            }
+        };
+        input.map_err(|source| Error::MissingInput { path, source })
+    }
+}
+
+fn file_name(scheme: &str, day: u32) -> String {
+    scheme
+        .replace("{day:02}", &format!("{:02}", day))
+        .replace("{day}", &day.to_string())
+}
+
+/// The lines of one paragraph of the input, records are separated by blank lines.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record {
+    /// 0-based index of the first line in the input
+    pub index: usize,
+    pub lines: Vec<String>,
+}
+
+impl Record {
+    /// the lines together with their 0-based index in the input
+    pub fn lines(&self) -> impl Iterator<Item = (usize, &str)> {
+        let index = self.index;
+        self.lines
+            .iter()
+            .enumerate()
+            .map(move |(i, line)| (index + i, line.as_str()))
+    }
+}
+
+/// Iterator over the records of a reader.
+///
+/// Several blank lines in a row separate records just like a single one,
+/// and the last record does not need a blank line after it.
+pub struct Records<R: BufRead> {
+    lines: io::Lines<R>,
+    index: usize,
+}
+
+impl<R: BufRead> Iterator for Records<R> {
+    type Item = io::Result<Record>;
+
+    fn next(&mut self) -> Option<io::Result<Record>> {
+        let mut record: Option<Record> = None;
+        for line in self.lines.by_ref() {
+            let line = match line {
+                Ok(line) => line,
+                Err(e) => return Some(Err(e)),
+            };
+            self.index += 1;
+            if line.is_empty() {
+                if record.is_some() {
+                    break;
+                }
+                continue;
+            }
+            record
+                .get_or_insert_with(|| Record {
+                    index: self.index - 1,
+                    lines: vec![],
+                })
+                .lines
+                .push(line);
+        }
+        record.map(Ok)
+    }
+}
+
+/// the records of everything the `reader` delivers
+pub fn records<R: BufRead>(reader: R) -> Records<R> {
+    Records {
+        lines: reader.lines(),
+        index: 0,
+    }
+}
+
+/// the records of an input that was already read
+pub fn split_records(input: &str) -> impl Iterator<Item = Record> + '_ {
+    records(input.as_bytes()).map(|record| record.expect("reading from a string cannot fail"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_with_line_numbers() {
+        let records: Vec<Record> = split_records("\na\nb\n\n\nc\r\n\nd").collect();
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[0].lines().collect::<Vec<_>>(), [(1, "a"), (2, "b")]);
+        assert_eq!(records[1].lines().collect::<Vec<_>>(), [(5, "c")]);
+        assert_eq!(records[2].lines().collect::<Vec<_>>(), [(7, "d")]);
+        assert_eq!(split_records("\n\n").count(), 0);
+    }
+}
@@ -0,0 +1,289 @@
+use std::fmt;
+
+/// A closed interval of integers, both `start` and `end` belong to it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Interval {
+    pub start: isize,
+    pub end: isize,
+}
+
+impl Interval {
+    /// the interval between `a` and `b`, in whatever order they come
+    pub fn new(a: isize, b: isize) -> Interval {
+        Interval {
+            start: a.min(b),
+            end: a.max(b),
+        }
+    }
+
+    /// Number of integers in the interval, never 0.
+    ///
+    /// # Panics
+    ///
+    /// if the interval holds more than `usize::MAX` integers, which only
+    /// `isize::MIN..=isize::MAX` does
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> usize {
+        self.start
+            .abs_diff(self.end)
+            .checked_add(1)
+            .expect("the interval holds more than usize::MAX integers")
+    }
+
+    pub fn contains(&self, x: isize) -> bool {
+        (self.start..=self.end).contains(&x)
+    }
+
+    /// true if all of `other` lies within this interval
+    pub fn contains_interval(&self, other: &Interval) -> bool {
+        self.start <= other.start && other.end <= self.end
+    }
+
+    pub fn overlaps(&self, other: &Interval) -> bool {
+        self.start <= other.end && other.start <= self.end
+    }
+
+    /// true if the intervals overlap or one starts right after the other ends
+    pub fn touches(&self, other: &Interval) -> bool {
+        self.start <= other.end.saturating_add(1) && other.start <= self.end.saturating_add(1)
+    }
+
+    pub fn intersection(&self, other: &Interval) -> Option<Interval> {
+        self.overlaps(other).then(|| Interval {
+            start: self.start.max(other.start),
+            end: self.end.min(other.end),
+        })
+    }
+
+    /// the joined interval, `None` if there would be a gap in between
+    pub fn union(&self, other: &Interval) -> Option<Interval> {
+        self.touches(other).then(|| Interval {
+            start: self.start.min(other.start),
+            end: self.end.max(other.end),
+        })
+    }
+}
+
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.start, self.end)
+    }
+}
+
+/// A set of integers, stored as sorted intervals that neither overlap nor touch.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct IntervalSet {
+    intervals: Vec<Interval>,
+}
+
+impl IntervalSet {
+    pub fn new() -> IntervalSet {
+        IntervalSet::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// the disjoint intervals, sorted by their start
+    pub fn intervals(&self) -> &[Interval] {
+        &self.intervals
+    }
+
+    /// Total number of integers in the set.
+    ///
+    /// # Panics
+    ///
+    /// if the set holds every `isize`, see [`Interval::len`]
+    pub fn len(&self) -> usize {
+        self.intervals.iter().map(Interval::len).sum()
+    }
+
+    /// smallest interval containing the whole set
+    pub fn span(&self) -> Option<Interval> {
+        Some(Interval {
+            start: self.intervals.first()?.start,
+            end: self.intervals.last()?.end,
+        })
+    }
+
+    pub fn insert(&mut self, interval: Interval) {
+        // everything before `first` ends too early, everything from `last` on starts too late
+        let first = self
+            .intervals
+            .partition_point(|i| i.end.saturating_add(1) < interval.start);
+        let last = self
+            .intervals
+            .partition_point(|i| i.start <= interval.end.saturating_add(1));
+        let merged = self.intervals[first..last]
+            .iter()
+            .fold(interval, |merged, i| merged.union(i).unwrap());
+        self.intervals.splice(first..last, [merged]);
+    }
+
+    /// the interval of the set containing `x`
+    fn find(&self, x: isize) -> Option<&Interval> {
+        let i = self.intervals.partition_point(|i| i.end < x);
+        self.intervals.get(i).filter(|i| i.contains(x))
+    }
+
+    pub fn contains(&self, x: isize) -> bool {
+        self.find(x).is_some()
+    }
+
+    /// true if every integer of `interval` is in the set
+    pub fn contains_interval(&self, interval: &Interval) -> bool {
+        self.find(interval.start)
+            .is_some_and(|i| i.contains_interval(interval))
+    }
+
+    pub fn union(&self, other: &IntervalSet) -> IntervalSet {
+        let mut union = self.clone();
+        for interval in other.intervals.iter() {
+            union.insert(*interval);
+        }
+        union
+    }
+
+    pub fn intersection(&self, other: &IntervalSet) -> IntervalSet {
+        let mut intersection = vec![];
+        let (mut a, mut b) = (0, 0);
+        while a < self.intervals.len() && b < other.intervals.len() {
+            let (x, y) = (&self.intervals[a], &other.intervals[b]);
+            intersection.extend(x.intersection(y));
+            if x.end < y.end {
+                a += 1;
+            } else {
+                b += 1;
+            }
+        }
+        IntervalSet {
+            intervals: intersection,
+        }
+    }
+
+    /// all integers of this set that are not in `other`
+    pub fn difference(&self, other: &IntervalSet) -> IntervalSet {
+        match self.span() {
+            Some(span) => self.intersection(&other.gaps(span)),
+            None => IntervalSet::new(),
+        }
+    }
+
+    /// the parts of `within` that are not covered by the set
+    pub fn gaps(&self, within: Interval) -> IntervalSet {
+        let mut gaps = vec![];
+        let mut next = within.start;
+        for interval in self.intervals.iter() {
+            if interval.end < next {
+                continue;
+            }
+            if interval.start > within.end {
+                break;
+            }
+            if interval.start > next {
+                gaps.push(Interval::new(next, interval.start - 1));
+            }
+            if interval.end >= within.end {
+                // nothing left to find, and `end + 1` could overflow
+                return IntervalSet { intervals: gaps };
+            }
+            next = interval.end + 1;
+        }
+        if next <= within.end {
+            gaps.push(Interval::new(next, within.end));
+        }
+        IntervalSet { intervals: gaps }
+    }
+}
+
+impl FromIterator<Interval> for IntervalSet {
+    fn from_iter<I: IntoIterator<Item = Interval>>(iter: I) -> IntervalSet {
+        let mut set = IntervalSet::new();
+        for interval in iter {
+            set.insert(interval);
+        }
+        set
+    }
+}
+
+impl fmt::Display for IntervalSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let intervals: Vec<String> = self.intervals.iter().map(|i| i.to_string()).collect();
+        write!(f, "{{{}}}", intervals.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(intervals: &[(isize, isize)]) -> IntervalSet {
+        intervals
+            .iter()
+            .map(|(a, b)| Interval::new(*a, *b))
+            .collect()
+    }
+
+    #[test]
+    fn intervals() {
+        let a = Interval::new(4, 2);
+        assert_eq!(a, Interval { start: 2, end: 4 });
+        assert_eq!(a.len(), 3);
+        assert!(a.contains_interval(&Interval::new(3, 4)));
+        assert!(!a.overlaps(&Interval::new(5, 6)));
+        assert!(a.touches(&Interval::new(5, 6)));
+        assert_eq!(a.union(&Interval::new(5, 6)), Some(Interval::new(2, 6)));
+        assert_eq!(a.union(&Interval::new(6, 6)), None);
+        assert_eq!(
+            a.intersection(&Interval::new(4, 9)),
+            Some(Interval::new(4, 4))
+        );
+        assert_eq!(Interval::new(isize::MIN + 1, isize::MAX).len(), usize::MAX);
+        // disjoint intervals leave out at least one integer, so their lengths add up
+        assert_eq!(set(&[(isize::MIN, -1), (1, isize::MAX)]).len(), usize::MAX);
+    }
+
+    #[test]
+    #[should_panic(expected = "more than usize::MAX")]
+    fn too_long() {
+        Interval::new(isize::MIN, isize::MAX).len();
+    }
+
+    #[test]
+    fn insert_merges() {
+        let s = set(&[(10, 12), (1, 2), (5, 6), (3, 3), (20, 30), (11, 19)]);
+        assert_eq!(s, set(&[(1, 3), (5, 6), (10, 30)]));
+        assert_eq!(s.to_string(), "{1-3, 5-6, 10-30}");
+        assert_eq!(s.len(), 26);
+        assert!(s.contains(5) && !s.contains(4));
+        assert!(s.contains_interval(&Interval::new(12, 30)));
+        assert!(!s.contains_interval(&Interval::new(2, 5)));
+    }
+
+    #[test]
+    fn algebra() {
+        let a = set(&[(0, 10), (20, 30)]);
+        let b = set(&[(5, 25)]);
+        assert_eq!(a.union(&b), set(&[(0, 30)]));
+        assert_eq!(a.intersection(&b), set(&[(5, 10), (20, 25)]));
+        assert_eq!(a.difference(&b), set(&[(0, 4), (26, 30)]));
+        assert_eq!(b.difference(&a), set(&[(11, 19)]));
+        assert_eq!(
+            a.gaps(Interval::new(-5, 35)),
+            set(&[(-5, -1), (11, 19), (31, 35)])
+        );
+        assert_eq!(a.gaps(Interval::new(2, 8)), IntervalSet::new());
+
+        let top = set(&[(0, 10), (isize::MAX - 5, isize::MAX)]);
+        let everything = Interval::new(isize::MIN, isize::MAX);
+        assert_eq!(
+            top.gaps(everything),
+            set(&[(isize::MIN, -1), (11, isize::MAX - 6)])
+        );
+        assert_eq!(
+            IntervalSet::from_iter([everything]).gaps(everything),
+            IntervalSet::new()
+        );
+    }
+}
@@ -0,0 +1,45 @@
+//! Solutions for the Advent of Code 2022.
+//!
+//! Every day lives in its own module `aocNN` and implements [`Solver`],
+//! the [`Registry`] maps the days to their solvers.
+
+#![allow(clippy::upper_case_acronyms)]
+
+pub mod answers;
+pub mod aoc01;
+pub mod aoc02;
+pub mod aoc03;
+pub mod aoc04;
+pub mod aoc05;
+pub mod aoc06;
+pub mod aoc07;
+pub mod aoc08;
+pub mod aoc09;
+pub mod aoc10;
+pub mod aoc11;
+pub mod aoc12;
+pub mod aoc13;
+pub mod aoc14;
+pub mod aoc15;
+pub mod aoc16;
+pub mod aoc17;
+pub mod aoc18;
+pub mod aoc19;
+pub mod aoc20;
+pub mod aoc21;
+pub mod aoc22;
+pub mod aoc23;
+pub mod aoc24;
+pub mod aoc25;
+pub mod bench;
+pub mod error;
+pub mod grid;
+pub mod input;
+pub mod interval;
+pub mod registry;
+pub mod runner;
+pub mod solver;
+
+pub use crate::error::{Error, Result};
+pub use crate::registry::Registry;
+pub use crate::solver::{Answer, Puzzle, Solver};
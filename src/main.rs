@@ -1,104 +1,134 @@
-extern crate queues;
-
 use chrono::{Datelike, Utc};
-use parse_int::parse;
 use std::env;
-use std::error::Error;
+use std::path::Path;
+use std::process::ExitCode;
 
-mod aoc01;
-mod aoc02;
-mod aoc03;
-mod aoc04;
-mod aoc05;
-mod aoc06;
-mod aoc07;
-mod aoc08;
-mod aoc09;
-mod aoc10;
-mod aoc11;
-mod aoc12;
-mod aoc13;
-mod aoc14;
-mod aoc15;
-mod aoc16;
-mod aoc17;
-mod aoc18;
-mod aoc19;
-mod aoc20;
-mod aoc21;
-mod aoc22;
-mod aoc23;
-mod aoc24;
-mod aoc25;
+mod cli;
 
-use crate::aoc01::aoc01;
-use crate::aoc02::aoc02;
-use crate::aoc03::aoc03;
-use crate::aoc04::aoc04;
-use crate::aoc05::aoc05;
-use crate::aoc06::aoc06;
-use crate::aoc07::aoc07;
-use crate::aoc08::aoc08;
-use crate::aoc09::aoc09;
-use crate::aoc10::aoc10;
-use crate::aoc11::aoc11;
-use crate::aoc12::aoc12;
-use crate::aoc13::aoc13;
-use crate::aoc14::aoc14;
-use crate::aoc15::aoc15;
-use crate::aoc16::aoc16;
-use crate::aoc17::aoc17;
-use crate::aoc18::aoc18;
-use crate::aoc19::aoc19;
-use crate::aoc20::aoc20;
-use crate::aoc21::aoc21;
-use crate::aoc22::aoc22;
-use crate::aoc23::aoc23;
-use crate::aoc24::aoc24;
-use crate::aoc25::aoc25;
+use aoc_2022_rust::answers::{Answers, Verdict, DEFAULT_ANSWERS};
+use aoc_2022_rust::{bench, runner, Error, Puzzle, Registry, Result};
 
-fn main() -> Result<(), Box<dyn Error>> {
-    let args: Vec<String> = env::args().collect();
-    let now = Utc::now();
-    let mut today = now.day();
+use crate::cli::Days;
 
-    if args.len() > 1 {
-        today = parse::<u32>(&args[1])?;
-        // if let Ok(d) = parse::<u32>(&args[1]) {
-        //     today = d;
-        // }
+fn main() -> ExitCode {
+    match run() {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            if let Error::Usage(_) = e {
+                eprintln!("\n{}", cli::USAGE);
+            }
+            ExitCode::FAILURE
+        }
     }
+}
 
-    println!("solving AOC day {}", today);
+fn run() -> Result<ExitCode> {
+    let options = cli::parse_args(env::args().skip(1))?;
+    if options.help {
+        println!("{}", cli::USAGE);
+        return Ok(ExitCode::SUCCESS);
+    }
 
-    match today {
-        1 => aoc01(),
-        2 => aoc02(),
-        3 => aoc03(),
-        4 => aoc04(),
-        5 => aoc05(),
-        6 => aoc06(),
-        7 => aoc07(),
-        8 => aoc08(),
-        9 => aoc09(),
-        10 => aoc10(),
-        11 => aoc11(),
-        12 => aoc12(),
-        13 => aoc13(),
-        14 => aoc14(),
-        15 => aoc15(),
-        16 => aoc16(),
-        17 => aoc17(),
-        18 => aoc18(),
-        19 => aoc19(),
-        20 => aoc20(),
-        21 => aoc21(),
-        22 => aoc22(),
-        23 => aoc23(),
-        24 => aoc24(),
-        25 => aoc25(),
+    let answers = if options.check {
+        let path = options
+            .answers
+            .as_deref()
+            .unwrap_or(Path::new(DEFAULT_ANSWERS));
+        Some(Answers::read(path)?)
+    } else {
+        None
+    };
 
-        _ => panic!(),
+    let registry = Registry::aoc2022();
+    let days: Vec<u32> = match &options.days {
+        Days::Single(day) => vec![day.unwrap_or_else(|| Utc::now().day())],
+        Days::All => registry.days().collect(),
+        Days::List(days) => days.clone(),
     };
-    Ok(())
+
+    if options.bench {
+        let runs = options.runs.unwrap_or(bench::DEFAULT_RUNS);
+        let mut benches = vec![];
+        for day in days {
+            eprintln!("benchmarking AOC day {}", day);
+            let solver = get_solver(&registry, day)?;
+            let input = options.input.read(day)?;
+            benches.push(bench::bench_day(day, solver, &input, runs)?);
+        }
+        if options.json {
+            println!("{}", bench::to_json(&benches));
+        } else {
+            println!("{}", bench::report(&benches));
+        }
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    let mut results = vec![];
+    let mut verdicts = vec![];
+    for day in days {
+        println!("solving AOC day {}", day);
+
+        let result = get_solver(&registry, day).and_then(|solver| {
+            let input = options.input.read(day)?;
+            runner::run_day(day, solver, &input)
+        });
+
+        match &result {
+            Ok(result) => {
+                if let Some(answers) = &answers {
+                    let [part1, part2] = answers.check(result);
+                    println!("part 1: {} {}", result.part1, part1);
+                    println!("part 2: {} {}", result.part2, part2);
+                    verdicts.extend([part1, part2]);
+                } else {
+                    println!("part 1: {}", result.part1);
+                    println!("part 2: {}", result.part2);
+                }
+            }
+            Err(e) => {
+                eprintln!("error: {}", e);
+                if answers.is_some() {
+                    println!("part 1: {}", Verdict::Error);
+                    println!("part 2: {}", Verdict::Error);
+                    verdicts.extend([Verdict::Error, Verdict::Error]);
+                }
+            }
+        }
+        results.push((day, result));
+    }
+
+    if !matches!(options.days, Days::Single(_)) {
+        println!("\n{}", runner::summary(&results));
+    }
+
+    let errors = results.iter().filter(|(_, r)| r.is_err()).count();
+    if answers.is_none() {
+        return Ok(if errors > 0 {
+            ExitCode::FAILURE
+        } else {
+            ExitCode::SUCCESS
+        });
+    }
+    let count = |f: fn(&Verdict) -> bool| verdicts.iter().filter(|v| f(v)).count();
+    let failed = count(Verdict::is_failure);
+    println!(
+        "\nchecked {} answers: {} passed, {} failed, {} missing",
+        verdicts.len(),
+        count(|v| *v == Verdict::Pass),
+        failed,
+        count(|v| *v == Verdict::Missing)
+    );
+    Ok(if failed > 0 {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    })
+}
+
+fn get_solver(registry: &Registry, day: u32) -> Result<&'static dyn Puzzle> {
+    registry.get(day).ok_or_else(|| Error::UnknownDay {
+        day,
+        known: registry.days().collect(),
+    })
 }
@@ -0,0 +1,86 @@
+use std::collections::BTreeMap;
+
+use crate::solver::Puzzle;
+
+use crate::aoc01::Aoc01;
+use crate::aoc02::Aoc02;
+use crate::aoc03::Aoc03;
+use crate::aoc04::Aoc04;
+use crate::aoc05::Aoc05;
+use crate::aoc06::Aoc06;
+use crate::aoc07::Aoc07;
+use crate::aoc08::Aoc08;
+use crate::aoc09::Aoc09;
+use crate::aoc10::Aoc10;
+use crate::aoc11::Aoc11;
+use crate::aoc12::Aoc12;
+use crate::aoc13::Aoc13;
+use crate::aoc14::Aoc14;
+use crate::aoc15::Aoc15;
+use crate::aoc16::Aoc16;
+use crate::aoc17::Aoc17;
+use crate::aoc18::Aoc18;
+use crate::aoc19::Aoc19;
+use crate::aoc20::Aoc20;
+use crate::aoc21::Aoc21;
+use crate::aoc22::Aoc22;
+use crate::aoc23::Aoc23;
+use crate::aoc24::Aoc24;
+use crate::aoc25::Aoc25;
+
+/// Maps day numbers to their solvers.
+#[derive(Default)]
+pub struct Registry {
+    solvers: BTreeMap<u32, &'static dyn Puzzle>,
+}
+
+impl Registry {
+    pub fn new() -> Registry {
+        Registry {
+            solvers: BTreeMap::new(),
+        }
+    }
+
+    /// all days of the 2022 calendar
+    pub fn aoc2022() -> Registry {
+        let mut registry = Registry::new();
+        registry.register(1, &Aoc01);
+        registry.register(2, &Aoc02);
+        registry.register(3, &Aoc03);
+        registry.register(4, &Aoc04);
+        registry.register(5, &Aoc05);
+        registry.register(6, &Aoc06);
+        registry.register(7, &Aoc07);
+        registry.register(8, &Aoc08);
+        registry.register(9, &Aoc09);
+        registry.register(10, &Aoc10);
+        registry.register(11, &Aoc11);
+        registry.register(12, &Aoc12);
+        registry.register(13, &Aoc13);
+        registry.register(14, &Aoc14);
+        registry.register(15, &Aoc15);
+        registry.register(16, &Aoc16);
+        registry.register(17, &Aoc17);
+        registry.register(18, &Aoc18);
+        registry.register(19, &Aoc19);
+        registry.register(20, &Aoc20);
+        registry.register(21, &Aoc21);
+        registry.register(22, &Aoc22);
+        registry.register(23, &Aoc23);
+        registry.register(24, &Aoc24);
+        registry.register(25, &Aoc25);
+        registry
+    }
+
+    pub fn register(&mut self, day: u32, solver: &'static dyn Puzzle) {
+        self.solvers.insert(day, solver);
+    }
+
+    pub fn get(&self, day: u32) -> Option<&'static dyn Puzzle> {
+        self.solvers.get(&day).copied()
+    }
+
+    pub fn days(&self) -> impl Iterator<Item = u32> + '_ {
+        self.solvers.keys().copied()
+    }
+}
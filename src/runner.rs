@@ -0,0 +1,103 @@
+use std::time::{Duration, Instant};
+
+use crate::error::Result;
+use crate::solver::{Answer, Puzzle};
+
+/// answers and timings of solving one day
+#[derive(Debug, Clone)]
+pub struct DayResult {
+    pub day: u32,
+    pub parse_time: Duration,
+    pub part1: Answer,
+    pub part1_time: Duration,
+    pub part2: Answer,
+    pub part2_time: Duration,
+}
+
+impl DayResult {
+    pub fn total_time(&self) -> Duration {
+        self.parse_time + self.part1_time + self.part2_time
+    }
+}
+
+pub fn run_day(day: u32, solver: &dyn Puzzle, input: &str) -> Result<DayResult> {
+    let start = Instant::now();
+    let input = solver.parse_input(input)?;
+    let parse_time = start.elapsed();
+
+    let start = Instant::now();
+    let part1 = solver.solve_part1(input.as_ref())?;
+    let part1_time = start.elapsed();
+
+    let start = Instant::now();
+    let part2 = solver.solve_part2(input.as_ref())?;
+    let part2_time = start.elapsed();
+
+    Ok(DayResult {
+        day,
+        parse_time,
+        part1,
+        part1_time,
+        part2,
+        part2_time,
+    })
+}
+
+/// renders the results as a table with one row per day, a day that failed
+/// shows its error instead of the answers
+pub fn summary(results: &[(u32, Result<DayResult>)]) -> String {
+    let header = ["day", "parse", "part 1", "time", "part 2", "time"];
+    let rows: Vec<Vec<String>> = results
+        .iter()
+        .map(|(day, result)| match result {
+            Ok(r) => vec![
+                day.to_string(),
+                format!("{:.2?}", r.parse_time),
+                r.part1.to_string(),
+                format!("{:.2?}", r.part1_time),
+                r.part2.to_string(),
+                format!("{:.2?}", r.part2_time),
+            ],
+            Err(e) => vec![day.to_string(), String::new(), format!("error: {}", e)],
+        })
+        .collect();
+    let total: Duration = results
+        .iter()
+        .filter_map(|(_, result)| result.as_ref().ok())
+        .map(|r| r.total_time())
+        .sum();
+    let mut total_row = vec![String::new(); header.len()];
+    total_row[0] = "total".to_string();
+    total_row[header.len() - 1] = format!("{:.2?}", total);
+
+    let mut widths: Vec<usize> = header.iter().map(|h| h.len()).collect();
+    // errors are the last cell of their row and may run past the columns
+    let complete = rows.iter().filter(|row| row.len() == header.len());
+    for row in complete.chain([&total_row]) {
+        for (width, cell) in widths.iter_mut().zip(row.iter()) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let line = |cells: &[String]| {
+        cells
+            .iter()
+            .zip(widths.iter())
+            .map(|(cell, width)| format!(" {:<width$} ", cell, width = width))
+            .collect::<Vec<_>>()
+            .join("|")
+            .trim_end()
+            .to_string()
+    };
+    let separator = widths
+        .iter()
+        .map(|w| "-".repeat(w + 2))
+        .collect::<Vec<_>>()
+        .join("+");
+
+    let mut table = vec![line(&header.map(|h| h.to_string())), separator.clone()];
+    table.extend(rows.iter().map(|row| line(row)));
+    table.push(separator);
+    table.push(line(&total_row));
+    table.join("\n")
+}
@@ -0,0 +1,88 @@
+use std::any::Any;
+use std::fmt;
+
+use crate::error::Result;
+
+/// The answer to one part of a puzzle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Number(i64),
+    Text(String),
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Number(n) => write!(f, "{}", n),
+            Answer::Text(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+macro_rules! answer_from_number {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(n: $t) -> Answer {
+                    Answer::Number(n as i64)
+                }
+            }
+        )*
+    };
+}
+
+answer_from_number!(i32, i64, isize, u16, u32, usize);
+
+impl From<String> for Answer {
+    fn from(s: String) -> Answer {
+        Answer::Text(s)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(s: &str) -> Answer {
+        Answer::Text(s.to_string())
+    }
+}
+
+/// A solver for a single day of the calendar.
+///
+/// `parse` turns the raw puzzle input into whatever the day works on,
+/// `part1` and `part2` solve the two halves of the puzzle on that input.
+pub trait Solver {
+    type Input: 'static;
+
+    fn parse(&self, input: &str) -> Result<Self::Input>;
+    fn part1(&self, input: &Self::Input) -> Result<Answer>;
+    fn part2(&self, input: &Self::Input) -> Result<Answer>;
+}
+
+/// Object-safe view of a [`Solver`], so days with different input types can
+/// live side by side in the registry.
+pub trait Puzzle: Sync {
+    fn parse_input(&self, input: &str) -> Result<Box<dyn Any>>;
+    fn solve_part1(&self, input: &dyn Any) -> Result<Answer>;
+    fn solve_part2(&self, input: &dyn Any) -> Result<Answer>;
+}
+
+impl<S: Solver + Sync> Puzzle for S {
+    fn parse_input(&self, input: &str) -> Result<Box<dyn Any>> {
+        Ok(Box::new(self.parse(input)?))
+    }
+
+    fn solve_part1(&self, input: &dyn Any) -> Result<Answer> {
+        self.part1(
+            input
+                .downcast_ref()
+                .expect("input parsed by another solver"),
+        )
+    }
+
+    fn solve_part2(&self, input: &dyn Any) -> Result<Answer> {
+        self.part2(
+            input
+                .downcast_ref()
+                .expect("input parsed by another solver"),
+        )
+    }
+}
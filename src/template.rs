@@ -1,32 +1,40 @@
 use min_max::*;
 use parse_int::parse;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
 use substring::Substring;
 
-fn aocXXX_1() {
-    println!("solving AOC day XXX part 1");
-    let reader = BufReader::new(File::open("input-XXX").unwrap());
+use crate::error::Result;
+use crate::solver::{Answer, Solver};
 
-    for (_index, line) in reader.lines().enumerate() {
-        let line = line.unwrap();
-    }
+fn aocXXX_1(lines: &[String]) -> usize {
+    let mut solution = 0;
+
+    for line in lines.iter() {}
 
-    println!("solution: {}", 0);
+    solution
 }
 
-fn aocXXX_2() {
-    println!("solving AOC day XXX part 2");
-    let reader = BufReader::new(File::open("input-XXX").unwrap());
+fn aocXXX_2(lines: &[String]) -> usize {
+    let mut solution = 0;
 
-    for (_index, line) in reader.lines().enumerate() {
-        let line = line.unwrap();
-    }
+    for line in lines.iter() {}
 
-    println!("solution: {}", 0);
+    solution
 }
 
-pub fn aocXXX() {
-    aocXXX_1();
-    aocXXX_2();
+pub struct AocXXX;
+
+impl Solver for AocXXX {
+    type Input = Vec<String>;
+
+    fn parse(&self, input: &str) -> Result<Vec<String>> {
+        Ok(input.lines().map(|line| line.to_string()).collect())
+    }
+
+    fn part1(&self, lines: &Vec<String>) -> Result<Answer> {
+        Ok(aocXXX_1(lines).into())
+    }
+
+    fn part2(&self, lines: &Vec<String>) -> Result<Answer> {
+        Ok(aocXXX_2(lines).into())
+    }
 }
@@ -0,0 +1,63 @@
+use std::cmp::Ordering;
+
+use aoc_2022_rust::aoc13::Packet;
+use aoc_2022_rust::aoc21::Aoc21;
+use aoc_2022_rust::aoc25::number_to_snafu;
+use aoc_2022_rust::{runner, Answer, Registry, Solver};
+
+#[test]
+fn registry_knows_every_day() {
+    let registry = Registry::aoc2022();
+    assert_eq!(
+        registry.days().collect::<Vec<u32>>(),
+        (1..=25).collect::<Vec<u32>>()
+    );
+}
+
+#[test]
+fn solve_through_the_registry() {
+    let solver = Registry::aoc2022().get(1).unwrap();
+    let result = runner::run_day(1, solver, "1000\n2000\n\n4000\n\n").unwrap();
+    assert_eq!(result.part1, Answer::from(4000));
+    assert_eq!(result.part2, Answer::from(7000));
+}
+
+#[test]
+fn summary_keeps_failed_days() {
+    let registry = Registry::aoc2022();
+    let results = vec![
+        (
+            1,
+            runner::run_day(1, registry.get(1).unwrap(), "1000\n\n2000\n"),
+        ),
+        (4, runner::run_day(4, registry.get(4).unwrap(), "1-2,3\n")),
+    ];
+    let table = runner::summary(&results);
+    let rows: Vec<&str> = table.lines().collect();
+    assert_eq!(rows.len(), 6);
+    assert!(rows[2].contains("| 2000 "));
+    assert!(rows[3].starts_with(" 4 ") && rows[3].contains("error: parse error at line 1"));
+}
+
+#[test]
+fn packets() {
+    let left = Packet::new("[[1],[2,3,4]]", 0).unwrap();
+    let right = Packet::new("[[1],4]", 1).unwrap();
+    assert_eq!(left.compare(&right), Ordering::Less);
+    assert!(Packet::new("[1,2", 2).is_err());
+}
+
+#[test]
+fn monkeys() {
+    let mut monkeys = Aoc21
+        .parse("root: pppw + sjmn\npppw: humn * lfqf\nlfqf: 4\nhumn: 5\nsjmn: 20\n")
+        .unwrap();
+    monkeys.resolve(false).unwrap();
+    monkeys.calc();
+    assert_eq!(monkeys.calc_human().unwrap(), 5);
+}
+
+#[test]
+fn snafu() {
+    assert_eq!(number_to_snafu(2022), "1=11-2");
+}